@@ -0,0 +1,389 @@
+use ggez::{audio::SoundData, graphics, Context, GameError, GameResult};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::resources::ResourceSource;
+use crate::ecs::systems::console_system::console::Console;
+use crate::ecs::{
+    atlas::Atlas,
+    ui::font::{BitmapFont, FontConfig, UiFont},
+    utils::constants::ASSET_WATCH_INTERVAL,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ImageHandle(usize);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AtlasHandle(usize);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ConfigHandle(usize);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FontHandle(usize);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SoundHandle(usize);
+
+/// Something that changed on disk and was reloaded in place, its handle stays valid.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AssetEvent {
+    ImageReloaded(ImageHandle),
+    AtlasReloaded(AtlasHandle),
+    ConfigReloaded(ConfigHandle),
+}
+
+struct WatchedFile {
+    /// Relative to the resources root.
+    path: String,
+    /// None for files that can't change, like the ones in an archive.
+    disk_path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+}
+
+impl WatchedFile {
+    fn new(source: &ResourceSource, path: &str) -> Self {
+        let disk_path = source.disk_path(path);
+        let modified = disk_path.as_deref().and_then(modified_time);
+        WatchedFile {
+            path: path.to_string(),
+            disk_path,
+            modified,
+        }
+    }
+
+    /// Returns true once per change of the file's modification time, or always when
+    /// `force`d for files on disk.
+    fn poll(&mut self, force: bool) -> bool {
+        let disk_path = match &self.disk_path {
+            Some(disk_path) => disk_path,
+            None => return false,
+        };
+        let modified = modified_time(disk_path);
+        if force || modified != self.modified {
+            self.modified = modified;
+            return true;
+        }
+        false
+    }
+}
+
+struct ImageAsset {
+    image: graphics::Image,
+    file: WatchedFile,
+}
+
+struct AtlasAsset {
+    atlas: Atlas,
+    image: ImageHandle,
+    file: WatchedFile,
+}
+
+struct ConfigAsset {
+    value: serde_json::Value,
+    file: WatchedFile,
+}
+
+/// Loads images, texture atlases and JSON configs once, hands out handles to them and
+/// reloads them when the files change on disk.
+pub struct AssetManager {
+    source: ResourceSource,
+    images: Vec<ImageAsset>,
+    atlases: Vec<AtlasAsset>,
+    configs: Vec<ConfigAsset>,
+    fonts: Vec<UiFont>,
+    sounds: Vec<SoundData>,
+    ui_font: Option<FontHandle>,
+    default_font: UiFont,
+    handles_by_path: HashMap<String, usize>,
+    since_last_poll: f32,
+    reload_requested: bool,
+}
+
+impl AssetManager {
+    pub fn new(source: ResourceSource) -> Self {
+        AssetManager {
+            source,
+            images: Vec::new(),
+            atlases: Vec::new(),
+            configs: Vec::new(),
+            fonts: Vec::new(),
+            sounds: Vec::new(),
+            ui_font: None,
+            default_font: UiFont::default(),
+            handles_by_path: HashMap::new(),
+            since_last_poll: 0.0,
+            reload_requested: false,
+        }
+    }
+
+    /// Loads an image relative to the resources root, e.g. `/dialog_box.png`.
+    pub fn load_image(&mut self, ctx: &mut Context, path: &str) -> GameResult<ImageHandle> {
+        let key = format!("image:{}", path);
+        if let Some(index) = self.handles_by_path.get(&key) {
+            return Ok(ImageHandle(*index));
+        }
+
+        let file = WatchedFile::new(&self.source, path);
+        let image = read_image(ctx, &self.source, path)?;
+
+        self.images.push(ImageAsset { image, file });
+        let index = self.images.len() - 1;
+        self.handles_by_path.insert(key, index);
+        Ok(ImageHandle(index))
+    }
+
+    /// Loads an Aseprite atlas, its json description and the png it describes.
+    pub fn load_atlas(
+        &mut self,
+        ctx: &mut Context,
+        json_path: &str,
+        image_path: &str,
+    ) -> GameResult<AtlasHandle> {
+        let key = format!("atlas:{}", json_path);
+        if let Some(index) = self.handles_by_path.get(&key) {
+            return Ok(AtlasHandle(*index));
+        }
+
+        let image = self.load_image(ctx, image_path)?;
+        let file = WatchedFile::new(&self.source, json_path);
+        let atlas = read_atlas(&self.source, json_path)?;
+
+        self.atlases.push(AtlasAsset { atlas, image, file });
+        let index = self.atlases.len() - 1;
+        self.handles_by_path.insert(key, index);
+        Ok(AtlasHandle(index))
+    }
+
+    pub fn load_config(&mut self, path: &str) -> GameResult<ConfigHandle> {
+        let key = format!("config:{}", path);
+        if let Some(index) = self.handles_by_path.get(&key) {
+            return Ok(ConfigHandle(*index));
+        }
+
+        let file = WatchedFile::new(&self.source, path);
+        let value = read_json(&self.source, path)?;
+
+        self.configs.push(ConfigAsset { value, file });
+        let index = self.configs.len() - 1;
+        self.handles_by_path.insert(key, index);
+        Ok(ConfigHandle(index))
+    }
+
+    /// Loads a font described by a json file, see `FontConfig`. Fonts aren't hot-reloaded.
+    pub fn load_font(&mut self, ctx: &mut Context, config_path: &str) -> GameResult<FontHandle> {
+        let key = format!("font:{}", config_path);
+        if let Some(index) = self.handles_by_path.get(&key) {
+            return Ok(FontHandle(*index));
+        }
+
+        let config: FontConfig =
+            serde_json::from_value(read_json(&self.source, config_path)?).map_err(|error| {
+                GameError::ResourceLoadError(format!("{}: {}", self.source.display(config_path), error))
+            })?;
+
+        let font = match config {
+            FontConfig::Ttf { path, size } => {
+                let bytes = self.source.read(&path)?;
+                UiFont::Ttf {
+                    font: graphics::Font::new_glyph_font_bytes(ctx, &bytes)?,
+                    size,
+                }
+            }
+            FontConfig::Bitmap {
+                image,
+                glyph_width,
+                glyph_height,
+                characters,
+                scale,
+                spacing,
+            } => UiFont::Bitmap(BitmapFont {
+                image: self.load_image(ctx, &image)?,
+                glyph_width,
+                glyph_height,
+                characters: characters.chars().collect(),
+                scale,
+                spacing,
+            }),
+        };
+
+        self.fonts.push(font);
+        let index = self.fonts.len() - 1;
+        self.handles_by_path.insert(key, index);
+        Ok(FontHandle(index))
+    }
+
+    /// Loads a wav, ogg, flac or mp3 file into memory. Sounds aren't hot-reloaded.
+    pub fn load_sound(&mut self, path: &str) -> GameResult<SoundHandle> {
+        let key = format!("sound:{}", path);
+        if let Some(index) = self.handles_by_path.get(&key) {
+            return Ok(SoundHandle(*index));
+        }
+
+        let sound = SoundData::from_bytes(&self.source.read(path)?);
+        if !sound.can_play() {
+            return Err(GameError::ResourceLoadError(format!("{}: unsupported audio format", path)));
+        }
+
+        self.sounds.push(sound);
+        let index = self.sounds.len() - 1;
+        self.handles_by_path.insert(key, index);
+        Ok(SoundHandle(index))
+    }
+
+    /// Whether `path`, relative to the resources root, exists.
+    pub fn exists(&self, path: &str) -> bool {
+        self.source.exists(path)
+    }
+
+    pub fn source(&self) -> &ResourceSource {
+        &self.source
+    }
+
+    pub fn set_ui_font(&mut self, handle: FontHandle) {
+        self.ui_font = Some(handle);
+    }
+
+    /// Font used by every UI widget, ggez's built-in one unless another was set.
+    pub fn ui_font(&self) -> &UiFont {
+        match self.ui_font {
+            Some(handle) => &self.fonts[handle.0],
+            None => &self.default_font,
+        }
+    }
+
+    pub fn image(&self, handle: ImageHandle) -> &graphics::Image {
+        &self.images[handle.0].image
+    }
+
+    pub fn atlas(&self, handle: AtlasHandle) -> &Atlas {
+        &self.atlases[handle.0].atlas
+    }
+
+    /// An atlas already loaded from `json_path`, none if nothing loaded it yet.
+    pub fn loaded_atlas(&self, json_path: &str) -> Option<&Atlas> {
+        let index = self.handles_by_path.get(&format!("atlas:{}", json_path))?;
+        Some(&self.atlases[*index].atlas)
+    }
+
+    pub fn atlas_image(&self, handle: AtlasHandle) -> ImageHandle {
+        self.atlases[handle.0].image
+    }
+
+    /// Sound data is reference counted, cloning it is cheap.
+    pub fn sound(&self, handle: SoundHandle) -> SoundData {
+        self.sounds[handle.0].clone()
+    }
+
+    /// Deserializes a cached config into `T`.
+    pub fn config<T: serde::de::DeserializeOwned>(&self, handle: ConfigHandle) -> GameResult<T> {
+        let asset = &self.configs[handle.0];
+        serde_json::from_value(asset.value.clone()).map_err(|error| {
+            GameError::ResourceLoadError(format!("{}: {}", self.source.display(&asset.file.path), error))
+        })
+    }
+
+    /// Writes `value` over `path` in the resources folder, where the watcher picks it up
+    /// like any other edit. Archived resources are read only.
+    pub fn save_config<T: serde::Serialize>(&self, path: &str, value: &T) -> GameResult {
+        let disk_path = self.source.disk_path(path).ok_or_else(|| {
+            GameError::FilesystemError(format!("{} is in an archive and can't be saved", self.source.display(path)))
+        })?;
+        let json = serde_json::to_string_pretty(value)
+            .map_err(|error| GameError::FilesystemError(error.to_string()))?;
+        fs::write(&disk_path, json + "\n")
+            .map_err(|error| GameError::FilesystemError(format!("{}: {}", disk_path.display(), error)))
+    }
+
+    /// Makes the next `poll_changes` reload every watched file, changed or not.
+    pub fn request_reload(&mut self) {
+        self.reload_requested = true;
+    }
+
+    /// Checks watched files every `ASSET_WATCH_INTERVAL` seconds and reloads the ones that
+    /// changed. A file that fails to reload keeps its previous contents.
+    pub fn poll_changes(&mut self, ctx: &mut Context) -> Vec<AssetEvent> {
+        self.since_last_poll += ggez::timer::delta(ctx).as_secs_f32();
+        let force = std::mem::replace(&mut self.reload_requested, false);
+        if self.since_last_poll < ASSET_WATCH_INTERVAL && !force {
+            return Vec::new();
+        }
+        self.since_last_poll = 0.0;
+
+        let mut events = Vec::new();
+
+        for (index, asset) in self.images.iter_mut().enumerate() {
+            if asset.file.poll(force) {
+                match read_image(ctx, &self.source, &asset.file.path) {
+                    Ok(image) => {
+                        asset.image = image;
+                        events.push(AssetEvent::ImageReloaded(ImageHandle(index)));
+                    }
+                    Err(error) => report_reload_error(&asset.file.path, &error),
+                }
+            }
+        }
+
+        for (index, asset) in self.atlases.iter_mut().enumerate() {
+            if asset.file.poll(force) {
+                match read_atlas(&self.source, &asset.file.path) {
+                    Ok(atlas) => {
+                        asset.atlas = atlas;
+                        events.push(AssetEvent::AtlasReloaded(AtlasHandle(index)));
+                    }
+                    Err(error) => report_reload_error(&asset.file.path, &error),
+                }
+            }
+        }
+
+        for (index, asset) in self.configs.iter_mut().enumerate() {
+            if asset.file.poll(force) {
+                match read_json(&self.source, &asset.file.path) {
+                    Ok(value) => {
+                        asset.value = value;
+                        events.push(AssetEvent::ConfigReloaded(ConfigHandle(index)));
+                    }
+                    Err(error) => report_reload_error(&asset.file.path, &error),
+                }
+            }
+        }
+
+        events
+    }
+
+}
+
+fn read_image(ctx: &mut Context, source: &ResourceSource, path: &str) -> GameResult<graphics::Image> {
+    let bytes = source.read(path)?;
+    let mut image = graphics::Image::from_bytes(ctx, &bytes)?;
+    image.set_filter(graphics::FilterMode::Nearest);
+    Ok(image)
+}
+
+fn read_atlas(source: &ResourceSource, path: &str) -> GameResult<Atlas> {
+    Atlas::parse_atlas_json(&source.read(path)?, &source.display(path))
+}
+
+fn read_json(source: &ResourceSource, path: &str) -> GameResult<serde_json::Value> {
+    let bytes = source.read(path)?;
+    serde_json::from_slice(&bytes).map_err(|error| {
+        GameError::ResourceLoadError(format!("{}: {}", source.display(path), error))
+    })
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn report_reload_error(path: &str, error: &GameError) {
+    eprintln!("Couldn't reload {}: {}", path, error);
+}
+
+pub fn register_console_commands(console: &mut Console) {
+    console.register("reload", "", "reloads every asset from disk", |_, shared, _, _| {
+        shared.asset_manager.request_reload();
+        Ok("Reloading assets".to_string())
+    });
+}
@@ -0,0 +1,150 @@
+use ggez::{GameError, GameResult};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::ecs::utils::constants::{DEV_RESOURCES_DIR, RESOURCES_ARCHIVE, RESOURCES_DIR, RESOURCES_ENV};
+
+/// Where the game's files come from. Every asset is read through here by its path relative
+/// to the resources root, e.g. `/player64.png`.
+pub enum ResourceSource {
+    /// A folder on disk, its files are watched for changes.
+    Directory(PathBuf),
+    /// A zip archive shipped with a release build, read into memory once.
+    Archive {
+        path: PathBuf,
+        files: HashMap<String, Vec<u8>>,
+    },
+}
+
+impl ResourceSource {
+    /// Finds the resources, trying in order:
+    ///
+    /// 1. `explicit`, from `--resources`, a folder or a zip archive
+    /// 2. the `WYE_RESOURCES` environment variable, same
+    /// 3. a `resources` folder next to the executable
+    /// 4. a `resources.zip` archive next to the executable
+    /// 5. `./src/resources`, for `cargo run` from the repository root
+    /// 6. the repository's `src/resources`, for `cargo run` from anywhere else
+    pub fn locate(explicit: Option<PathBuf>) -> GameResult<ResourceSource> {
+        // An explicitly requested location has to work, there's no falling back from it
+        let requested = explicit.or_else(|| std::env::var_os(RESOURCES_ENV).map(PathBuf::from));
+        if let Some(path) = requested {
+            return ResourceSource::open(&path);
+        }
+
+        let mut candidates = Vec::new();
+        if let Some(exe_dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)) {
+            candidates.push(exe_dir.join("resources"));
+            candidates.push(exe_dir.join(RESOURCES_ARCHIVE));
+        }
+        candidates.push(PathBuf::from(RESOURCES_DIR));
+        candidates.push(PathBuf::from(DEV_RESOURCES_DIR));
+
+        match candidates.iter().find(|path| path.exists()) {
+            Some(path) => ResourceSource::open(path),
+            None => Err(GameError::ResourceNotFound(
+                "resources".to_string(),
+                candidates
+                    .into_iter()
+                    .map(|path| (path, GameError::FilesystemError("not found".to_string())))
+                    .collect(),
+            )),
+        }
+    }
+
+    /// Opens a folder, or a zip archive when `path` is a file.
+    pub fn open(path: &Path) -> GameResult<ResourceSource> {
+        if path.is_dir() {
+            Ok(ResourceSource::Directory(path.to_path_buf()))
+        } else if path.is_file() {
+            Ok(ResourceSource::Archive {
+                path: path.to_path_buf(),
+                files: read_archive(path)?,
+            })
+        } else {
+            Err(GameError::ResourceNotFound(
+                path.display().to_string(),
+                vec![(path.to_path_buf(), GameError::FilesystemError("not found".to_string()))],
+            ))
+        }
+    }
+
+    pub fn read(&self, path: &str) -> GameResult<Vec<u8>> {
+        match self {
+            ResourceSource::Directory(_) => {
+                let disk_path = self.disk_path(path).unwrap_or_default();
+                fs::read(&disk_path).map_err(|error| {
+                    GameError::ResourceNotFound(
+                        disk_path.display().to_string(),
+                        vec![(disk_path.clone(), GameError::from(error))],
+                    )
+                })
+            }
+            ResourceSource::Archive { files, .. } => {
+                files.get(archive_key(path)).cloned().ok_or_else(|| {
+                    GameError::ResourceNotFound(self.display(path), Vec::new())
+                })
+            }
+        }
+    }
+
+    pub fn exists(&self, path: &str) -> bool {
+        match self {
+            ResourceSource::Directory(_) => self.disk_path(path).is_some_and(|path| path.exists()),
+            ResourceSource::Archive { files, .. } => files.contains_key(archive_key(path)),
+        }
+    }
+
+    /// The file on disk behind `path`, none for archived resources.
+    pub fn disk_path(&self, path: &str) -> Option<PathBuf> {
+        match self {
+            ResourceSource::Directory(root) => Some(root.join(path.trim_start_matches('/'))),
+            ResourceSource::Archive { .. } => None,
+        }
+    }
+
+    /// How `path` is named in messages, e.g. `resources.zip/player64.png`.
+    pub fn display(&self, path: &str) -> String {
+        let root = match self {
+            ResourceSource::Directory(root) => root,
+            ResourceSource::Archive { path, .. } => path,
+        };
+        root.join(path.trim_start_matches('/')).display().to_string()
+    }
+
+    /// The resources folder, for ggez's own filesystem.
+    pub fn directory(&self) -> Option<&Path> {
+        match self {
+            ResourceSource::Directory(root) => Some(root),
+            ResourceSource::Archive { .. } => None,
+        }
+    }
+}
+
+fn archive_key(path: &str) -> &str {
+    path.trim_start_matches('/')
+}
+
+fn read_archive(path: &Path) -> GameResult<HashMap<String, Vec<u8>>> {
+    let to_error = |error: &dyn std::fmt::Display| {
+        GameError::ResourceLoadError(format!("{}: {}", path.display(), error))
+    };
+
+    let file = File::open(path).map_err(|error| to_error(&error))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|error| to_error(&error))?;
+    let mut files = HashMap::new();
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|error| to_error(&error))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes).map_err(|error| to_error(&error))?;
+        // Archives zipped from the folder itself or from its parent both work
+        let name = entry.name().trim_start_matches("resources/").to_string();
+        files.insert(name, bytes);
+    }
+    Ok(files)
+}
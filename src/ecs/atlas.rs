@@ -1,5 +1,4 @@
-use ggez::{Context, graphics::{self, spritebatch::SpriteBatch}};
-use std::path::Path;
+use ggez::{GameError, GameResult, graphics::{self, spritebatch::SpriteBatch}};
 
 use super::sprites::sprite::Sprite;
 
@@ -35,23 +34,25 @@ pub struct Atlas {
 }
 
 impl Atlas {
-    pub fn parse_atlas_json(texture_atlas_file: &Path) -> Self {
-        use std::fs::File;
-        use std::io::BufReader;
+    /// Parses an Aseprite atlas description, `name` is only used in errors.
+    pub fn parse_atlas_json(json: &[u8], name: &str) -> GameResult<Self> {
+        serde_json::from_slice(json).map_err(|error| {
+            GameError::ResourceLoadError(format!("Couldn't create texture atlas {}: {}", name, error))
+        })
+    }
 
-        let file = File::open(texture_atlas_file).expect("Couldn't find the texture_atlas file");
-        let buf_reader = BufReader::new(file);
-        serde_json::from_reader(buf_reader).expect("Couldn't create texture atlas")
+    pub fn frame_names(&self) -> impl Iterator<Item = &str> {
+        self.frames.iter().map(|frame| frame.filename.as_str())
     }
 
-    /// Returns a sprite from the Atlas.
-    pub fn create_sprite(&self, sprite_name: &str) -> Sprite {
+    /// Returns a sprite from the Atlas, an error if it has no frame named `sprite_name`.
+    pub fn create_sprite(&self, sprite_name: &str) -> GameResult<Sprite> {
         let width = self.meta.size.w as f32;
         let height = self.meta.size.h as f32;
         let atlas_rect = graphics::Rect::new(0.0, 0.0, width, height);
 
         if let Some(sprite_data) = self.frames.iter().find(|d| d.filename == sprite_name) {
-            Sprite::new(
+            Ok(Sprite::new(
                 graphics::Rect::fraction(
                     sprite_data.frame.x as f32,
                     sprite_data.frame.y as f32,
@@ -61,15 +62,14 @@ impl Atlas {
                 ),
                 sprite_data.frame.w as f32,
                 sprite_data.frame.h as f32,
-            )
+            ))
         } else {
-            unimplemented!("Not handling failure to find sprite");
+            Err(GameError::ResourceLoadError(format!("No sprite {} in the atlas", sprite_name)))
         }
     }
 }
 
-pub fn create_batch_sprite(ctx: &mut Context, file_name: String) -> SpriteBatch {
-    let image = graphics::Image::new(ctx, file_name).unwrap();
+pub fn create_batch_sprite(image: graphics::Image) -> SpriteBatch {
     let mut batch = graphics::spritebatch::SpriteBatch::new(image);
     batch.set_filter(graphics::FilterMode::Nearest);
     batch
@@ -0,0 +1,65 @@
+use ggez::audio::SpatialSource;
+
+use crate::ecs::systems::audio_system::audio::{AudioCategory, Attenuation};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EmitterMode {
+    /// Plays continuously while in range.
+    Loop,
+    /// Plays once every `min_interval` to `max_interval` seconds, picked at random.
+    Every { min_interval: f32, max_interval: f32 },
+}
+
+/// A sound coming from an entity, heard louder the closer it is to the listener.
+pub struct AudioEmitter {
+    pub sound: String,
+    pub category: AudioCategory,
+    pub mode: EmitterMode,
+    pub attenuation: Attenuation,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    pub volume: f32,
+    pub pitch: f32,
+    /// Seconds until the next play of an `Every` emitter.
+    pub cooldown: f32,
+    /// Playing source of a `Loop` emitter, followed around as the listener moves.
+    pub source: Option<SpatialSource>,
+}
+
+impl AudioEmitter {
+    pub fn new(sound: &str, category: AudioCategory, mode: EmitterMode) -> Self {
+        let cooldown = match mode {
+            EmitterMode::Loop => 0.0,
+            EmitterMode::Every { min_interval, .. } => min_interval,
+        };
+        AudioEmitter {
+            sound: sound.to_string(),
+            category,
+            mode,
+            attenuation: Attenuation::InverseSquare,
+            min_distance: 80.0,
+            max_distance: 600.0,
+            volume: 1.0,
+            pitch: 1.0,
+            cooldown,
+            source: None,
+        }
+    }
+
+    pub fn attenuation(mut self, attenuation: Attenuation, min_distance: f32, max_distance: f32) -> Self {
+        self.attenuation = attenuation;
+        self.min_distance = min_distance;
+        self.max_distance = max_distance;
+        self
+    }
+
+    pub fn volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    pub fn pitch(mut self, pitch: f32) -> Self {
+        self.pitch = pitch;
+        self
+    }
+}
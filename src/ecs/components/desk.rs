@@ -1,4 +1,4 @@
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub struct Desk {
     pub animation_id: i32,
     pub desk_type: i32,
@@ -0,0 +1,9 @@
+use crate::ecs::systems::physics_system::physics::Position;
+
+/// Takes the player to another area when they walk into it.
+#[derive(Clone, Debug)]
+pub struct Door {
+    pub to: String,
+    /// Where the player appears in `to`.
+    pub spawn: Position,
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use crate::ecs::{systems::input_system::interaction::Interaction, utils::localization::Localization};
+
+/// An object the player can interact with, shown in the dialogue box under `name`. Texts
+/// are localization keys.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Interactable {
+    pub name: String,
+    #[serde(flatten)]
+    pub kind: InteractableKind,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InteractableKind {
+    /// Only has something to say.
+    Inspect { text: String },
+    /// Does `action` every time it's used.
+    Use { text: String, action: UseAction },
+    /// Switched on and off, with a story flag following it if `flag` is set.
+    Toggle {
+        on: bool,
+        on_text: String,
+        off_text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        flag: Option<String>,
+    },
+    /// Holds items, all taken the first time it's opened.
+    Container {
+        items: Vec<String>,
+        empty_text: String,
+    },
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum UseAction {
+    GiveItem { item: String },
+    SetFlag { flag: String },
+}
+
+impl Interactable {
+    /// What a desk does by default: its monitor is switched on and off.
+    pub fn monitor() -> Self {
+        Interactable {
+            name: "object.desk".to_string(),
+            kind: InteractableKind::Toggle {
+                on: true,
+                on_text: "object.monitor_on".to_string(),
+                off_text: "object.monitor_off".to_string(),
+                flag: None,
+            },
+        }
+    }
+
+    /// Carries out the interaction, returning what's said about it. A container's text lists
+    /// what was taken, already translated.
+    pub fn interact(
+        &mut self,
+        inventory: &mut Vec<String>,
+        flags: &mut HashMap<String, bool>,
+        localization: &Localization,
+    ) -> Interaction {
+        let dialog = match &mut self.kind {
+            InteractableKind::Inspect { text } => text.clone(),
+            InteractableKind::Use { text, action } => {
+                match action {
+                    UseAction::GiveItem { item } => inventory.push(item.clone()),
+                    UseAction::SetFlag { flag } => {
+                        flags.insert(flag.clone(), true);
+                    }
+                }
+                text.clone()
+            }
+            InteractableKind::Toggle { on, on_text, off_text, flag } => {
+                *on = !*on;
+                if let Some(flag) = flag {
+                    flags.insert(flag.clone(), *on);
+                }
+                if *on { on_text.clone() } else { off_text.clone() }
+            }
+            InteractableKind::Container { items, empty_text } if items.is_empty() => empty_text.clone(),
+            InteractableKind::Container { items, .. } => {
+                let taken: Vec<&str> = items.iter().map(|item| localization.tr(item)).collect();
+                let dialog = format!("{} {}", localization.tr("object.took"), taken.join(", "));
+                inventory.append(items);
+                dialog
+            }
+        };
+        Interaction::new(None, None, dialog)
+    }
+
+    /// Whether it's switched on, none if it isn't a toggle.
+    pub fn is_on(&self) -> Option<bool> {
+        match self.kind {
+            InteractableKind::Toggle { on, .. } => Some(on),
+            _ => None,
+        }
+    }
+
+    /// Every localization key it uses.
+    pub fn text_keys(&self) -> Vec<String> {
+        let mut keys = vec![self.name.clone()];
+        match &self.kind {
+            InteractableKind::Inspect { text } | InteractableKind::Use { text, .. } => keys.push(text.clone()),
+            InteractableKind::Toggle { on_text, off_text, .. } => {
+                keys.push(on_text.clone());
+                keys.push(off_text.clone());
+            }
+            InteractableKind::Container { items, empty_text } => {
+                keys.extend(items.iter().cloned());
+                keys.push(empty_text.clone());
+                keys.push("object.took".to_string());
+            }
+        }
+        keys
+    }
+}
@@ -0,0 +1,29 @@
+use ggez::graphics::Color;
+
+use crate::ecs::systems::physics_system::physics::Position;
+
+/// Light given off by an entity, added on top of the ambient light by `lighting_system`.
+#[derive(Copy, Clone, Debug)]
+pub struct Light {
+    pub color: Color,
+    /// Distance in world units at which it fades out completely.
+    pub radius: f32,
+    pub intensity: f32,
+    /// Switched off lights give no light, e.g. a monitor turned off through its desk.
+    pub on: bool,
+    /// From the entity's position to where the light comes from.
+    pub offset: Position,
+}
+
+impl Light {
+    /// The glow of a desk's monitor.
+    pub fn monitor() -> Self {
+        Light {
+            color: Color::new(0.55, 0.75, 1.0, 1.0),
+            radius: 110.0,
+            intensity: 0.8,
+            on: true,
+            offset: Position { x: 0.0, y: -20.0 },
+        }
+    }
+}
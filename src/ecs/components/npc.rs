@@ -1,4 +1,12 @@
+use crate::ecs::assets::asset_manager::ImageHandle;
+
+#[derive(Clone, Debug)]
 pub struct Npc {
     pub id: u32,
     pub name: String,
+    /// Frame of the NPC atlas it's drawn with.
+    pub sprite: String,
+    pub avatar: ImageHandle,
+    /// Pitch of the dialogue blips, 1.0 plays the blip as recorded.
+    pub voice_pitch: f32,
 }
@@ -0,0 +1,5 @@
+/// Decoration, drawn with a frame of the office atlas.
+#[derive(Clone, Debug)]
+pub struct Prop {
+    pub frame: String,
+}
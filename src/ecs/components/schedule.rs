@@ -0,0 +1,57 @@
+use crate::ecs::systems::{
+    input_system::interaction::Interaction,
+    physics_system::physics::{Physics, Position},
+};
+
+/// Where an entry of a schedule sends the NPC.
+#[derive(Copy, Clone, Debug)]
+pub enum SchedulePlace {
+    /// Stays wherever it is.
+    Stay,
+    At(Position),
+    /// Out of the office.
+    Away,
+}
+
+#[derive(Clone, Debug)]
+pub struct ScheduleEntry {
+    /// Minutes since midnight it starts at.
+    pub minute: f32,
+    pub activity: String,
+    pub place: SchedulePlace,
+    pub interaction: Option<Interaction>,
+}
+
+/// An NPC's day, followed by `schedule_system`.
+#[derive(Clone, Debug)]
+pub struct Schedule {
+    /// Sorted by time.
+    pub entries: Vec<ScheduleEntry>,
+    /// What it says when the current entry doesn't say otherwise.
+    pub main_interaction: Option<Interaction>,
+    /// The entry being followed, none until the schedule first runs.
+    pub current: Option<usize>,
+    /// Where it's walking to.
+    pub target: Option<Position>,
+    /// Walking out, it goes away once it reaches `target`.
+    pub leaving: bool,
+    /// Its physics while it's out of the office, so it's neither drawn nor in the way.
+    pub away: Option<Physics>,
+}
+
+impl Schedule {
+    pub fn new(entries: Vec<ScheduleEntry>, main_interaction: Option<Interaction>) -> Self {
+        Schedule {
+            entries,
+            main_interaction,
+            current: None,
+            target: None,
+            leaving: false,
+            away: None,
+        }
+    }
+
+    pub fn activity(&self) -> Option<&str> {
+        self.current.map(|index| self.entries[index].activity.as_str())
+    }
+}
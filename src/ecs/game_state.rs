@@ -1,204 +1,1105 @@
-use super::{atlas::Atlas, utils::constants::*};
+use super::utils::constants::*;
 use super::{
-    atlas::{self},
+    assets::asset_manager::{self, AssetEvent, AtlasHandle, ConfigHandle},
+    scenes::{
+        dialogue_editor_scene::DialogueEditorScene,
+        dialogue_scene::DialogueScene,
+        pause_scene::PauseScene,
+        scene::{Scene, SceneTransition},
+        shared_state::SharedState,
+    },
     sprites::office_sprite::OfficeSprite,
+    sprites::prop_sprite::PropSprite,
     sprites::player_sprite::PlayerSprite,
     sprites::npc_sprite::NpcSprite,
     sprites::tile_sprite::{create_tiles, TileSprite},
     systems::{
-        input_system::input_system,
+        area_system::{
+            area::AreaFade,
+            area_system::{self, door_in_reach, draw_area_fade, draw_doors},
+        },
+        console_system::{
+            console::{parse_arg, Console},
+            console_system::{self, draw_console},
+        },
+        debug_system::{debug_system::{self, draw_debug_overlay}, frame_stats::FrameStats},
+        editor_system::{editor::Editor, editor_system::{self, *}},
+        input_system::input_system::{self, InteractionSound},
         input_system::interaction::*,
+        lighting_system::{
+            light_map::LightMap,
+            lighting_system::{self, ambient_at, brightness, screen_lights, LightingConfig},
+        },
         physics_system::physics::*,
-        physics_system::physics_system::*,
-        render_system::{camera::*, render_system::*},
+        physics_system::physics_system::{self, *},
+        render_system::{camera::*, render_queue::RenderQueue, render_system::{self, *}},
+        schedule_system::{
+            clock::{Clock, ClockConfig},
+            schedule_system::{self, draw_clock, update_schedules},
+        },
     },
 };
-use super::{components::npc::Npc, components::desk::Desk, utils::npcs_json_loader::load_npcs};
+use super::components::audio_emitter::{AudioEmitter, EmitterMode};
+use super::systems::audio_system::audio::{Attenuation, AudioCategory, Listener};
+use super::sprites::player_sprite::animation_sprite_index;
+use super::{components::npc::Npc, components::desk::Desk, components::door::Door, components::interactable::Interactable, components::light::Light, components::prop::Prop, components::schedule::Schedule, utils::localization::Localization, utils::rng};
+use super::utils::area_loader::{load_areas, AreaJson, AreasConfig};
+use super::utils::npcs_json_loader::{load_npcs, load_npcs_config, NpcJson, PointJson};
+use super::utils::scene_loader::{load_scene, SceneEntity, SceneEntityKind, SceneFile};
 use ggez::*;
-use ggez::{event::*, graphics::spritebatch::SpriteBatch, mint::Vector2};
+use ggez::event::*;
 use rand::Rng;
+use std::collections::{BTreeMap, HashMap};
 
 pub type EntityIndex = usize;
 
+/// The office itself, the scene where the player walks around.
 // #[derive(Copy, Clone)]
 pub struct GameState {
     pub physics_components: Vec<Option<Physics>>,
     pub npcs_components: Vec<Option<Npc>>,
     pub player_physics: Physics,
-    pub current_interaction: Option<Interaction>,
     pub npcs_interactions: Vec<Option<Interaction>>,
     pub desk_components: Vec<Option<Desk>>,
+    pub emitter_components: Vec<Option<AudioEmitter>>,
+    pub prop_components: Vec<Option<Prop>>,
+    pub schedule_components: Vec<Option<Schedule>>,
+    pub light_components: Vec<Option<Light>>,
+    pub interactable_components: Vec<Option<Interactable>>,
+    pub door_components: Vec<Option<Door>>,
     pub camera: Camera,
     pub world_size: Size,
+    pub console: Console,
+    /// The player walks through everything, toggled from the console.
+    pub noclip: bool,
+    pub inventory: Vec<String>,
+    pub flags: HashMap<String, bool>,
+    pub frame_stats: FrameStats,
+    /// Open while the office is being edited, see `editor_system`.
+    pub editor: Option<Editor>,
+    pub clock: Clock,
+    /// None with lighting turned off.
+    pub light_map: Option<LightMap>,
+    lighting: LightingConfig,
+    areas: AreasConfig,
+    /// Name of the area the player is in.
+    area: String,
+    /// Set while going through a door.
+    area_fade: Option<AreaFade>,
+    /// How each area the player left was left, so emptied containers and switched off
+    /// monitors stay that way when coming back.
+    left_areas: HashMap<String, SceneFile>,
+    npcs_config: ConfigHandle,
+    clock_config: Option<ConfigHandle>,
+    lighting_config: Option<ConfigHandle>,
+    areas_config: Option<ConfigHandle>,
+    /// Spots of the office from the npcs config, where schedules send NPCs.
+    places: BTreeMap<String, PointJson>,
+    scene_config: Option<ConfigHandle>,
+    player_atlas: AtlasHandle,
+    npcs_atlas: AtlasHandle,
+    floor_atlas: AtlasHandle,
+    office_atlas: AtlasHandle,
     tiles: Vec<Box<TileSprite>>,
     player_sprite: PlayerSprite,
-    player_sprite_batch: SpriteBatch,
     npcs_sprite: NpcSprite,
-    npcs_sprite_batch: SpriteBatch,
-    floor_sprite_batch: SpriteBatch,
-    office_sprite_batch: SpriteBatch,
     office_sprite: OfficeSprite,
+    prop_sprite: PropSprite,
+    render_queue: RenderQueue,
     frames: usize,
 }
 
-impl ggez::event::EventHandler<GameError> for GameState {
-    fn update(&mut self, ctx: &mut Context) -> GameResult {
-        let player_mov_actions = input_system::player_movements(ctx);
+impl Scene for GameState {
+    fn update(&mut self, ctx: &mut Context, shared: &mut SharedState) -> GameResult<SceneTransition> {
+        self.frame_stats.record_frame(ggez::timer::delta(ctx).as_secs_f32());
+
+        // The next area is entered once the screen is black
+        let entering = self
+            .area_fade
+            .as_mut()
+            .and_then(|fade| fade.update(ggez::timer::delta(ctx).as_secs_f32()));
+        if let Some((area, spawn)) = entering {
+            if let Err(error) = self.enter_area(ctx, shared, &area, spawn) {
+                eprintln!("Couldn't enter {}: {}", area, error);
+            }
+        }
+        if matches!(&self.area_fade, Some(fade) if fade.is_done()) {
+            self.area_fade = None;
+        }
+
+        let player_mov_actions = if self.console.is_open() || self.editor.is_some() || self.area_fade.is_some() {
+            Vec::new()
+        } else {
+            self.frame_stats
+                .measure("input", || input_system::player_movements(ctx, &shared.settings.controls))
+        };
 
-        match self.current_interaction {
-            Some(_) => (),
-            None => {
-                self.player_physics = update_player_physics(
-                    ctx,
-                    &player_mov_actions,
+        // Time stands still while the office is being worked on
+        if !self.console.is_open() && self.editor.is_none() {
+            let dt = ggez::timer::delta(ctx).as_secs_f32();
+            self.clock.update(dt);
+            let entrance = self.places.get(ENTRANCE_PLACE).map(|point| point.position());
+            self.frame_stats.measure("schedules", || {
+                update_schedules(
+                    &self.clock,
+                    dt,
+                    entrance,
                     &self.player_physics,
-                    &self.physics_components,
-                    &self.world_size,
-                );
-                self.camera
-                    .maybe_update(ctx, &self.player_physics, &self.world_size);
+                    &mut self.schedule_components,
+                    &mut self.physics_components,
+                    &mut self.npcs_interactions,
+                )
+            });
+        }
+
+        let no_obstacles = Vec::new();
+        self.player_physics = self.frame_stats.measure("physics", || {
+            update_player_physics(
+                ctx,
+                &player_mov_actions,
+                &self.player_physics,
+                if self.noclip { &no_obstacles } else { &self.physics_components },
+                &self.world_size,
+            )
+        });
+        if self.editor.is_none() && self.area_fade.is_none() {
+            let door = door_in_reach(
+                &self.player_physics,
+                &player_mov_actions,
+                &self.physics_components,
+                &self.door_components,
+            );
+            if let Some(door) = door {
+                self.area_fade = Some(AreaFade::new(&door.to, Some(door.spawn)));
             }
         }
+        // Nothing to interact with while editing
+        self.player_physics.current_focus = match self.editor {
+            Some(_) => None,
+            None => find_focus(&self.player_physics, &self.physics_components, |index| self.can_interact(index)),
+        };
+        if self.editor.is_some() && !self.console.is_open() {
+            update_editor(ctx, self);
+        }
+        self.frame_stats.measure("camera", || {
+            self.camera
+                .update(ctx, &self.player_physics, &self.world_size)
+        });
 
-        Ok(())
+        self.frame_stats.measure("audio", || {
+            let audio_system = &mut shared.audio_system;
+            audio_system.play_music(ctx, "office");
+            audio_system.footsteps(ctx, self.player_physics.walking, animation_sprite_index(self.frames));
+            audio_system.update_emitters(
+                ctx,
+                &Listener::from_camera(&self.camera),
+                &mut self.emitter_components,
+                &self.physics_components,
+            );
+        });
+
+        Ok(SceneTransition::None)
     }
 
-    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+    fn draw(&mut self, ctx: &mut Context, shared: &mut SharedState) -> GameResult {
         graphics::clear(ctx, graphics::Color::from_rgb(130, 90, 44));
+        let draw_start = std::time::Instant::now();
 
-        let draw_param = graphics::DrawParam::new().scale(Vector2 { x: 1.0, y: 1.0 });
-
-        draw_tiles(
-            ctx,
+        let floor_image = shared.asset_manager.atlas_image(self.floor_atlas);
+        let floor = self.current_area().map_or(graphics::Color::WHITE, AreaJson::floor_color);
+        self.render_queue.set_tint(Some(floor));
+        draw_tiles(&mut self.render_queue, &self.camera, &mut self.tiles, floor_image);
+        self.render_queue.set_tint(None);
+        let highlight = self
+            .player_physics
+            .current_focus
+            .map(|index| (index, focus_tint(self.frames)));
+        draw_npcs(
+            &mut self.render_queue,
             &self.camera,
-            &mut self.tiles,
-            &mut self.floor_sprite_batch,
-            draw_param,
-        )?;
-        draw_world_bounds(ctx, &self.camera, &self.world_size)?;
-        draw_npcs(ctx, &self.camera, &self.physics_components, &self.npcs_components, &mut self.npcs_sprite_batch, &mut self.npcs_sprite, draw_param)?;
-        draw_interactions(ctx, &self.camera.size, &self.npcs_components, &self.current_interaction, &self.player_physics.current_focus)?;
+            &self.physics_components,
+            &self.npcs_components,
+            &mut self.npcs_sprite,
+            highlight,
+        );
         draw_sprite(
-            ctx,
+            &mut self.render_queue,
             &self.camera,
             &self.player_physics,
-            &mut self.player_sprite_batch,
             &mut self.player_sprite,
             self.frames,
-            draw_param,
-        )?;
-
+        );
 
         for (index, component) in self.physics_components.iter().enumerate() {
-            match component {
-                Some(physics) => match index {
-                    i if !self.desk_components[i].is_none() => {
-                        let desk: &Desk = &self.desk_components[i].unwrap();
-                        draw_sprite_component(
-                            ctx,
-                            &self.camera,
-                            physics,
-                            &mut self.office_sprite_batch,
-                            &mut self.office_sprite,
-                            self.frames,
-                            draw_param,
-                            desk
-                        )?;
-                    }
-                    _ => (),
-                },
-                None => (),
+            let tint = highlight.filter(|(focus, _)| *focus == index).map(|(_, tint)| tint);
+            self.render_queue.set_tint(tint);
+            if let (Some(physics), Some(desk)) = (component, &self.desk_components[index]) {
+                draw_sprite_component(
+                    &mut self.render_queue,
+                    &self.camera,
+                    physics,
+                    &mut self.office_sprite,
+                    self.frames,
+                    desk,
+                );
+            }
+            if let (Some(physics), Some(prop)) = (component, &self.prop_components[index]) {
+                draw_sprite_component(
+                    &mut self.render_queue,
+                    &self.camera,
+                    physics,
+                    &mut self.prop_sprite,
+                    self.frames,
+                    prop,
+                );
             }
         }
+        self.render_queue.set_tint(None);
 
-        graphics::present(ctx)?;
+        self.render_queue.flush(ctx, &shared.asset_manager)?;
+        draw_doors(ctx, shared, &self.camera, &self.physics_components, &self.door_components)?;
+        // The editor shows the office as it is, whatever the time
+        if let (Some(light_map), None) = (self.light_map.as_mut(), &self.editor) {
+            let ambient = ambient_at(&self.lighting, self.clock.minute);
+            let area_lights = self.areas.areas.get(&self.area).into_iter().flat_map(|area| area.lights.iter());
+            let lights = screen_lights(
+                &self.camera,
+                self.lighting.lights.iter().chain(area_lights),
+                brightness(ambient),
+                self.frames,
+                &self.physics_components,
+                &self.light_components,
+                &self.desk_components,
+            );
+            light_map.draw(ctx, &shared.viewport, ambient, &lights)?;
+        }
+        draw_world_bounds(ctx, &self.camera, &self.world_size)?;
+        // The overlay's own drawing isn't counted
+        self.frame_stats.record("render", draw_start.elapsed().as_secs_f32());
+        let focused = self
+            .player_physics
+            .current_focus
+            .and_then(|index| self.physics_components[index]);
+        if let (Some(focused), false) = (focused, self.console.is_open()) {
+            draw_interaction_prompt(ctx, shared, &self.camera, &focused, self.frames)?;
+        }
+        if self.editor.is_none() {
+            draw_clock(ctx, shared, &self.clock)?;
+        }
+        if let Some(fade) = &self.area_fade {
+            draw_area_fade(ctx, shared, fade)?;
+        }
+        if shared.debug_overlay {
+            draw_debug_overlay(ctx, shared, self)?;
+        }
+        draw_editor(ctx, shared, self)?;
+        if self.console.is_open() {
+            draw_console(ctx, shared, &self.console)?;
+        }
 
         self.frames += 1;
 
         Ok(())
     }
 
-    fn key_down_event(&mut self, _ctx: &mut Context, key: KeyCode, _mods: KeyMods, _: bool) {
-        self.current_interaction = input_system::key_down_event_interaction(self, key);
+    fn key_down(
+        &mut self,
+        ctx: &mut Context,
+        shared: &mut SharedState,
+        key: KeyCode,
+    ) -> GameResult<SceneTransition> {
+        if self.console.is_open() {
+            if let Some(line) = self.console.key_down(key) {
+                console_system::execute(ctx, shared, self, &line);
+            }
+            return Ok(SceneTransition::None);
+        }
+
+        if key == KeyCode::F4 || (self.editor.is_some() && key == KeyCode::Escape) {
+            toggle_editor(shared, self);
+            return Ok(SceneTransition::None);
+        }
+        if self.editor.is_some() && key != KeyCode::Grave {
+            editor_key_down(ctx, shared, self, key)?;
+            return Ok(SceneTransition::None);
+        }
+
+        let transition = match key {
+            KeyCode::Grave => {
+                self.console.toggle();
+                SceneTransition::None
+            }
+            KeyCode::Equals => {
+                self.camera.set_zoom(self.camera.zoom + CAMERA_ZOOM_STEP);
+                SceneTransition::None
+            }
+            KeyCode::Minus => {
+                self.camera.set_zoom(self.camera.zoom - CAMERA_ZOOM_STEP);
+                SceneTransition::None
+            }
+            KeyCode::F5 => SceneTransition::Push(Box::new(DialogueEditorScene::new(ctx, shared)?)),
+            KeyCode::Escape => {
+                shared.play_ui_sound(ctx, Some(InteractionSound::Select));
+                SceneTransition::Push(Box::new(PauseScene::new(shared)))
+            }
+            KeyCode::Return if self.area_fade.is_none() => match input_system::begin_interaction(self, &shared.localization) {
+                Some((speaker, interaction)) => {
+                    shared.play_ui_sound(ctx, input_system::interaction_sound(&None, &Some(interaction.clone())));
+                    SceneTransition::Push(Box::new(DialogueScene::new(shared, speaker, interaction)))
+                }
+                None => SceneTransition::None,
+            },
+            _ => SceneTransition::None,
+        };
+        Ok(transition)
+    }
+
+    /// The console and the editor get every key as typed.
+    fn wants_raw_keys(&self) -> bool {
+        self.console.is_open() || self.editor.is_some()
+    }
+
+    fn text_input(&mut self, _ctx: &mut Context, _shared: &mut SharedState, ch: char) {
+        if self.console.is_open() {
+            self.console.text_input(ch);
+        }
+    }
+
+    fn mouse_down(&mut self, ctx: &mut Context, shared: &mut SharedState, button: MouseButton, position: Position) {
+        if let Err(error) = editor_mouse_down(ctx, shared, self, button, position) {
+            eprintln!("Couldn't edit the office: {}", error);
+        }
+    }
+
+    fn mouse_up(&mut self, _ctx: &mut Context, _shared: &mut SharedState, _button: MouseButton, _position: Position) {
+        editor_mouse_up(self);
+    }
+
+    fn mouse_motion(&mut self, _ctx: &mut Context, _shared: &mut SharedState, position: Position) {
+        editor_mouse_motion(self, position);
+    }
+
+    fn resize(&mut self, shared: &SharedState) {
+        self.camera.size = shared.viewport.virtual_size;
+    }
+
+    fn asset_event(&mut self, ctx: &mut Context, shared: &mut SharedState, event: AssetEvent) -> GameResult {
+        self.apply_asset_event(ctx, shared, event)
     }
 }
 
 impl GameState {
-    pub fn new(ctx: &mut Context) -> GameState {
+    pub fn new(ctx: &mut Context, shared: &mut SharedState) -> GameResult<GameState> {
         let npcs_components = Vec::new();
         let desk_components = Vec::new();
+        let emitter_components = Vec::new();
+        let prop_components = Vec::new();
+        let schedule_components = Vec::new();
+        let light_components = Vec::new();
+        let interactable_components = Vec::new();
+        let door_components = Vec::new();
         let physics_components = Vec::new();
         let player_physics = initial_player_physics();
         let npcs_interactions = Vec::new();
 
-        let player_atlas =
-            Atlas::parse_atlas_json(std::path::Path::new("src/resources/player64.json"));
-        let player_sprite_batch = atlas::create_batch_sprite(ctx, "/player64.png".to_string());
-
-        let npcs_atlas =
-            Atlas::parse_atlas_json(std::path::Path::new("src/resources/npcs64.json"));
-        let npcs_sprite_batch = atlas::create_batch_sprite(ctx, "/npcs64.png".to_string());
-
-        let floor_atlas = Atlas::parse_atlas_json(std::path::Path::new("src/resources/floor.json"));
-        let floor_sprite_batch = atlas::create_batch_sprite(ctx, "/floor.png".to_string());
-
-        let office_atlas =
-            Atlas::parse_atlas_json(std::path::Path::new("src/resources/office.json"));
-        let office_sprite_batch = atlas::create_batch_sprite(ctx, "/office.png".to_string());
+        let asset_manager = &mut shared.asset_manager;
+        let player_atlas = asset_manager.load_atlas(ctx, "/player64.json", "/player64.png")?;
+        let npcs_atlas = asset_manager.load_atlas(ctx, NPCS_ATLAS, "/npcs64.png")?;
+        let floor_atlas = asset_manager.load_atlas(ctx, "/floor.json", "/floor.png")?;
+        let office_atlas = asset_manager.load_atlas(ctx, OFFICE_ATLAS, "/office.png")?;
+        let npcs_config = asset_manager.load_config(NPCS_CONFIG)?;
+        let areas_config = if asset_manager.exists(AREAS_CONFIG) {
+            Some(asset_manager.load_config(AREAS_CONFIG)?)
+        } else {
+            None
+        };
+        let areas = match areas_config {
+            Some(areas_config) => load_areas(asset_manager, areas_config)?,
+            None => AreasConfig::default(),
+        };
+        let clock_config = if asset_manager.exists(CLOCK_CONFIG) {
+            Some(asset_manager.load_config(CLOCK_CONFIG)?)
+        } else {
+            None
+        };
+        let clock = match clock_config {
+            Some(clock_config) => Clock::new(&asset_manager.config(clock_config)?),
+            None => Clock::new(&ClockConfig::default()),
+        };
+        let lighting_config = if asset_manager.exists(LIGHTING_CONFIG) {
+            Some(asset_manager.load_config(LIGHTING_CONFIG)?)
+        } else {
+            None
+        };
+        let lighting = match lighting_config {
+            Some(lighting_config) => asset_manager.config(lighting_config)?,
+            None => LightingConfig::default(),
+        };
+        let light_map = LightMap::new(ctx, shared.viewport.virtual_size);
 
-        let camera = Camera::new(player_physics.position.clone());
+        let mut camera = Camera::new(player_physics.position);
+        camera.size = shared.viewport.virtual_size;
 
         let mut game_state = GameState {
             physics_components,
             npcs_components,
             desk_components,
+            emitter_components,
+            prop_components,
+            schedule_components,
+            light_components,
+            interactable_components,
+            door_components,
             player_physics,
-            current_interaction: None,
             npcs_interactions,
             camera,
             world_size: Size {
                 width: INTIAL_WORLD_W,
                 height: INTIAL_WORLD_H,
             },
-            player_sprite: PlayerSprite::new(&player_atlas),
-            player_sprite_batch,
-            npcs_sprite: NpcSprite::new(&npcs_atlas),
-            npcs_sprite_batch,
-            tiles: create_tiles(&floor_atlas),
-            floor_sprite_batch,
-            office_sprite_batch,
-            office_sprite: OfficeSprite::new(&office_atlas),
+            console: Console::new(),
+            noclip: false,
+            inventory: Vec::new(),
+            flags: HashMap::new(),
+            frame_stats: FrameStats::new(),
+            editor: None,
+            clock,
+            light_map: Some(light_map),
+            lighting,
+            area: areas.start.clone(),
+            areas,
+            area_fade: None,
+            left_areas: HashMap::new(),
+            player_sprite: PlayerSprite::new(
+                asset_manager.atlas(player_atlas),
+                asset_manager.atlas_image(player_atlas),
+            )?,
+            npcs_sprite: NpcSprite::new(
+                asset_manager.atlas(npcs_atlas),
+                asset_manager.atlas_image(npcs_atlas),
+            )?,
+            // Both set up by `load_area`
+            tiles: Vec::new(),
+            office_sprite: OfficeSprite::new(
+                asset_manager.atlas(office_atlas),
+                asset_manager.atlas_image(office_atlas),
+            )?,
+            prop_sprite: PropSprite::new(
+                asset_manager.atlas(office_atlas),
+                asset_manager.atlas_image(office_atlas),
+            )?,
+            render_queue: RenderQueue::new(),
+            npcs_config,
+            scene_config: None,
+            clock_config,
+            lighting_config,
+            areas_config,
+            places: BTreeMap::new(),
+            player_atlas,
+            npcs_atlas,
+            floor_atlas,
+            office_atlas,
             frames: 0,
         };
-        game_state.load_initial_components();
-        game_state
+        let start = shared.start_area.clone().unwrap_or_else(|| game_state.area.clone());
+        game_state.load_area(ctx, shared, &start, None)?;
+        game_state.report_missing_translations(&shared.localization);
+
+        console_system::register_console_commands(&mut game_state.console);
+        asset_manager::register_console_commands(&mut game_state.console);
+        physics_system::register_console_commands(&mut game_state.console);
+        debug_system::register_console_commands(&mut game_state.console);
+        editor_system::register_console_commands(&mut game_state.console);
+        schedule_system::register_console_commands(&mut game_state.console);
+        lighting_system::register_console_commands(&mut game_state.console);
+        area_system::register_console_commands(&mut game_state.console);
+        render_system::register_console_commands(&mut game_state.console);
+        GameState::register_console_commands(&mut game_state.console);
+        if let Some(path) = shared.console_script.clone() {
+            if let Err(error) = console_system::run_script(ctx, shared, &mut game_state, &path) {
+                eprintln!("Couldn't run the console script {}", error);
+            }
+        }
+        Ok(game_state)
     }
 
-    // Components loading
-    fn load_initial_components(&mut self) {
-        self.add_npcs();
-        self.add_desks();
+    fn register_console_commands(console: &mut Console) {
+        console.register("spawn", "npc <name>", "spawns an NPC from the npcs config next to the player", |ctx, shared, game, args| {
+            let name = match args {
+                ["npc", name] => name,
+                _ => return Err("expected npc <name>".to_string()),
+            };
+            let npcs = load_npcs(&shared.asset_manager, game.npcs_config).map_err(|error| error.to_string())?;
+            let npc_data = npcs
+                .iter()
+                .find(|npc| npc.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| format!("no NPC named {}", name))?;
+
+            let mut physics = generate_npc_physics();
+            physics.position = Position {
+                x: game.player_physics.position.x + 2.0 * HUMANOID_W,
+                y: game.player_physics.position.y,
+            };
+            let index = game
+                .spawn_npc(ctx, shared, npc_data, physics)
+                .map_err(|error| error.to_string())?;
+            Ok(format!("Spawned {} as entity {}", npc_data.name, index))
+        });
+        console.register("give", "<item>", "adds an item to the player's inventory", |_, _, game, args| {
+            let item = args.first().ok_or("missing item")?;
+            game.inventory.push(item.to_string());
+            Ok(format!("Inventory: {}", game.inventory.join(", ")))
+        });
+        console.register("set_flag", "<name> [true|false]", "sets a story flag, true by default", |_, _, game, args| {
+            let name = args.first().ok_or("missing name")?;
+            let value = if args.len() > 1 { parse_arg(args, 1, "value")? } else { true };
+            game.flags.insert(name.to_string(), value);
+            Ok(format!("{} = {}", name, value))
+        });
+        console.register("inspect", "<entity>", "prints an entity's components", |_, _, game, args| {
+            let index: EntityIndex = parse_arg(args, 0, "entity")?;
+            if index >= game.physics_components.len() {
+                return Err(format!("no entity {}, there are {}", index, game.physics_components.len()));
+            }
+
+            let mut lines = vec![format!("Entity {}", index)];
+            if let Some(physics) = &game.physics_components[index] {
+                lines.push(format!("  Physics: {:?}", physics));
+            }
+            if let Some(npc) = &game.npcs_components[index] {
+                lines.push(format!("  Npc: {:?}", npc));
+            }
+            if let Some(interaction) = &game.npcs_interactions[index] {
+                lines.push(format!("  Interaction: {}", interaction.dialog));
+            }
+            if let Some(desk) = &game.desk_components[index] {
+                lines.push(format!("  Desk: {:?}", desk));
+            }
+            if let Some(emitter) = &game.emitter_components[index] {
+                lines.push(format!("  AudioEmitter: {} ({:?}, {:?})", emitter.sound, emitter.category, emitter.mode));
+            }
+            if let Some(prop) = &game.prop_components[index] {
+                lines.push(format!("  Prop: {:?}", prop));
+            }
+            if let Some(light) = &game.light_components[index] {
+                lines.push(format!("  Light: {:?}", light));
+            }
+            if let Some(interactable) = &game.interactable_components[index] {
+                lines.push(format!("  Interactable: {:?}", interactable));
+            }
+            if let Some(door) = &game.door_components[index] {
+                lines.push(format!("  Door: {:?}", door));
+            }
+            if let Some(schedule) = &game.schedule_components[index] {
+                let activity = schedule.activity().unwrap_or("-");
+                let state = if schedule.away.is_some() { ", away" } else { "" };
+                lines.push(format!("  Schedule: {} of {} entries{}", activity, schedule.entries.len(), state));
+            }
+            Ok(lines.join("\n"))
+        });
+    }
+
+    // Hot-reload
+    fn apply_asset_event(&mut self, ctx: &mut Context, shared: &mut SharedState, event: AssetEvent) -> GameResult {
+        match event {
+            // Images are looked up by handle every frame, nothing to rebuild
+            AssetEvent::ImageReloaded(_) => (),
+            // An atlas missing a frame is reported and the old sprites stay
+            AssetEvent::AtlasReloaded(atlas) => {
+                let reloaded = shared.asset_manager.atlas(atlas);
+                let image = shared.asset_manager.atlas_image(atlas);
+                if atlas == self.player_atlas {
+                    self.player_sprite = PlayerSprite::new(reloaded, image)?;
+                } else if atlas == self.npcs_atlas {
+                    self.npcs_sprite = NpcSprite::new(reloaded, image)?;
+                } else if atlas == self.floor_atlas {
+                    self.tiles = create_tiles(reloaded, &self.world_size)?;
+                } else if atlas == self.office_atlas {
+                    let office_sprite = OfficeSprite::new(reloaded, image)?;
+                    self.prop_sprite = PropSprite::new(reloaded, image)?;
+                    self.office_sprite = office_sprite;
+                }
+            }
+            AssetEvent::ConfigReloaded(config) if config == self.npcs_config => {
+                // A broken edit is reported and the NPCs stay as they were
+                if let Err(error) = self.reload_npcs(ctx, shared) {
+                    eprintln!("Couldn't reload the NPCs: {}", error);
+                }
+            }
+            // The editor's unsaved changes win over the file, a broken edit is reported and
+            // the area stays as it is
+            AssetEvent::ConfigReloaded(config) if Some(config) == self.scene_config => {
+                if self.editor.is_none() {
+                    match load_scene(&shared.asset_manager, config, &self.scene_path(), &self.desk_types()) {
+                        Ok(scene) => self.load_scene(ctx, shared, &scene)?,
+                        Err(error) => eprintln!("Couldn't reload the scene: {}", error),
+                    }
+                }
+            }
+            AssetEvent::ConfigReloaded(config) if Some(config) == self.areas_config => {
+                // A broken edit is reported and the areas stay as they were
+                if let Err(error) = self.reload_areas(ctx, shared, config) {
+                    eprintln!("Couldn't reload the areas: {}", error);
+                }
+            }
+            AssetEvent::ConfigReloaded(config) if Some(config) == self.clock_config => {
+                // Only the pace changes, jumping to the start time would undo the day. A broken
+                // edit is reported and the clock keeps its pace.
+                match shared.asset_manager.config::<ClockConfig>(config) {
+                    Ok(clock_config) => self.clock.minutes_per_second = clock_config.minutes_per_second.max(0.0),
+                    Err(error) => eprintln!("Couldn't reload the clock: {}", error),
+                }
+            }
+            AssetEvent::ConfigReloaded(config) if Some(config) == self.lighting_config => {
+                // A broken edit is reported and the lighting stays as it was
+                match shared.asset_manager.config(config) {
+                    Ok(lighting) => self.lighting = lighting,
+                    Err(error) => eprintln!("Couldn't reload the lighting: {}", error),
+                }
+            }
+            // Other configs are string tables, already reloaded by the scene stack
+            AssetEvent::ConfigReloaded(_) => self.report_missing_translations(&shared.localization),
+        }
+        Ok(())
     }
 
-    fn add_npcs(&mut self) {
-        let npcs = load_npcs();
+    /// Applies an edited areas file, entering the current area again to pick up its doors,
+    /// size and scene. The editor's unsaved changes are left alone until it's closed.
+    fn reload_areas(&mut self, ctx: &mut Context, shared: &mut SharedState, config: ConfigHandle) -> GameResult {
+        self.areas = load_areas(&shared.asset_manager, config)?;
+        if self.editor.is_some() {
+            return Ok(());
+        }
+        if self.areas.areas.contains_key(&self.area) {
+            let area = self.area.clone();
+            self.enter_area(ctx, shared, &area, Some(self.player_physics.position))
+        } else {
+            let start = self.areas.start.clone();
+            self.enter_area(ctx, shared, &start, None)
+        }
+    }
+
+    /// Applies an edited npcs config to the NPCs already in the world, matched by id.
+    fn reload_npcs(&mut self, ctx: &mut Context, shared: &mut SharedState) -> GameResult {
+        let config = load_npcs_config(&shared.asset_manager, self.npcs_config)?;
+        self.places = config.places;
+        let npcs = config.npcs;
+
+        // NPCs taken out of the config leave, new ones arrive
+        let removed: Vec<EntityIndex> = self
+            .npcs_components
+            .iter()
+            .enumerate()
+            .filter(|(_, npc)| matches!(npc, Some(npc) if !npcs.iter().any(|npc_data| npc_data.id == npc.id)))
+            .map(|(index, _)| index)
+            .collect();
+        for index in removed {
+            self.remove_entity(index);
+        }
+
         for npc_data in npcs.iter() {
-            self.add_entity(
-                Some(generate_npc_physics()),
-                Some(Npc {
-                    id: npc_data.id,
-                    name: npc_data.name.clone(),
-                }),
-                npc_data.main_interaction.clone(),
+            let entities: Vec<EntityIndex> = self
+                .npcs_components
+                .iter()
+                .enumerate()
+                .filter(|(_, npc)| matches!(npc, Some(npc) if npc.id == npc_data.id))
+                .map(|(index, _)| index)
+                .collect();
+
+            if entities.is_empty() {
+                self.spawn_npc(ctx, shared, npc_data, npc_physics(npc_data))?;
+                continue;
+            }
+            let avatar = shared.asset_manager.load_image(ctx, &npc_data.avatar_path())?;
+            for index in entities {
+                self.npcs_components[index] = Some(npc_data.to_npc(avatar));
+                self.npcs_interactions[index] = npc_data.main_interaction.clone();
+                let away = self.schedule_components[index].take().and_then(|schedule| schedule.away);
+                match npc_data.to_schedule(&self.places) {
+                    Some(mut schedule) => {
+                        schedule.away = away;
+                        self.schedule_components[index] = Some(schedule);
+                    }
+                    // Without a schedule it's back in the office for good
+                    None => {
+                        if let Some(away) = away {
+                            self.physics_components[index] = Some(away);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn report_missing_translations(&self, localization: &Localization) {
+        let schedule_interactions = self
+            .schedule_components
+            .iter()
+            .flatten()
+            .flat_map(|schedule| schedule.entries.iter().filter_map(|entry| entry.interaction.as_ref()));
+        let used_keys: Vec<String> = self
+            .npcs_interactions
+            .iter()
+            .flatten()
+            .chain(schedule_interactions)
+            .flat_map(|interaction| interaction.text_keys())
+            .chain(self.interactable_components.iter().flatten().flat_map(Interactable::text_keys))
+            .collect();
+
+        for (locale, keys) in localization.missing_keys(&used_keys) {
+            eprintln!("Locale {} is missing {} keys: {}", locale, keys.len(), keys.join(", "));
+        }
+    }
+
+    // Areas
+    /// Leaves the current area for `name`, see `load_area`. The area left is kept as it is
+    /// for the next visit.
+    fn enter_area(&mut self, ctx: &mut Context, shared: &mut SharedState, name: &str, spawn: Option<Position>) -> GameResult {
+        self.left_areas.insert(self.area.clone(), self.scene_file());
+        self.load_area(ctx, shared, name, spawn)
+    }
+
+    /// Unloads the current area's entities and loads `name`'s, as it was left if it was
+    /// visited before, otherwise from its scene file or laid out at random without one. The
+    /// player is put at `spawn` or the area's own spawn point.
+    fn load_area(&mut self, ctx: &mut Context, shared: &mut SharedState, name: &str, spawn: Option<Position>) -> GameResult {
+        let area = self
+            .areas
+            .areas
+            .get(name)
+            .cloned()
+            .ok_or_else(|| GameError::ResourceLoadError(format!("no area named {}", name)))?;
+        self.area = name.to_string();
+        self.world_size = area.size();
+        self.tiles = create_tiles(shared.asset_manager.atlas(self.floor_atlas), &self.world_size)?;
+
+        self.scene_config = if shared.asset_manager.exists(&area.scene) {
+            Some(shared.asset_manager.load_config(&area.scene)?)
+        } else {
+            None
+        };
+        match (self.left_areas.remove(name), self.scene_config) {
+            (Some(scene), _) => self.load_scene(ctx, shared, &scene)?,
+            (None, Some(scene_config)) => {
+                let scene = load_scene(&shared.asset_manager, scene_config, &area.scene, &self.desk_types())?;
+                self.load_scene(ctx, shared, &scene)?;
+            }
+            (None, None) => {
+                self.clear_entities();
+                if area.random_layout {
+                    self.add_npcs(ctx, shared)?;
+                    self.add_desks();
+                }
+                self.add_doors();
+            }
+        }
+
+        let player = &mut self.player_physics;
+        player.position = spawn.unwrap_or_else(|| area.spawn_position());
+        player.position.clamp_self(
+            &player.size,
+            &Position {
+                x: self.world_size.width,
+                y: self.world_size.height,
+            },
+        );
+        self.camera.jump_to(player.position, &self.world_size);
+        Ok(())
+    }
+
+    /// Fades out of the current area and into `name`, see `enter_area`.
+    pub fn travel(&mut self, name: &str, spawn: Option<Position>) -> Result<(), String> {
+        if !self.areas.areas.contains_key(name) {
+            return Err(format!("no area named {}", name));
+        }
+        if self.editor.is_some() {
+            return Err("close the editor first".to_string());
+        }
+        self.area_fade = Some(AreaFade::new(name, spawn));
+        Ok(())
+    }
+
+    pub fn area(&self) -> &str {
+        &self.area
+    }
+
+    pub fn area_names(&self) -> Vec<String> {
+        self.areas.areas.keys().cloned().collect()
+    }
+
+    fn current_area(&self) -> Option<&AreaJson> {
+        self.areas.areas.get(&self.area)
+    }
+
+    /// Scene file of the current area, which the editor saves to.
+    pub fn scene_path(&self) -> String {
+        self.current_area()
+            .map_or_else(|| SCENE_FILE.to_string(), |area| area.scene.clone())
+    }
+
+    // Components loading
+    /// Replaces every entity of the area with the scene's, plus the area's doors.
+    pub fn load_scene(&mut self, ctx: &mut Context, shared: &mut SharedState, scene: &SceneFile) -> GameResult {
+        self.clear_entities();
+        let config = load_npcs_config(&shared.asset_manager, self.npcs_config)?;
+        self.places = config.places;
+        for entity in scene.entities.iter() {
+            self.spawn_scene_entity(ctx, shared, entity, &config.npcs)?;
+        }
+        self.add_doors();
+        Ok(())
+    }
+
+    fn clear_entities(&mut self) {
+        self.physics_components.clear();
+        self.npcs_components.clear();
+        self.npcs_interactions.clear();
+        self.desk_components.clear();
+        self.emitter_components.clear();
+        self.prop_components.clear();
+        self.schedule_components.clear();
+        self.light_components.clear();
+        self.interactable_components.clear();
+        self.door_components.clear();
+        self.player_physics.current_focus = None;
+    }
+
+    /// Adds the current area's doors, which come from the areas file rather than its scene.
+    fn add_doors(&mut self) {
+        let doors = self.current_area().map_or_else(Vec::new, |area| area.doors.clone());
+        for door in doors {
+            let physics = Physics::new(
+                Position { x: door.x, y: door.y },
+                Size {
+                    width: door.width,
+                    height: door.height,
+                },
+                0.0,
+                graphics::Color::WHITE,
+                None,
                 None,
             );
+            let index = self.add_entity(Some(physics), None, None, None, None, None);
+            self.door_components[index] = Some(Door {
+                to: door.to,
+                spawn: door.spawn.position(),
+            });
         }
     }
 
+    /// Adds an entity of a scene file, NPCs missing from `npcs` are skipped.
+    pub fn spawn_scene_entity(
+        &mut self,
+        ctx: &mut Context,
+        shared: &mut SharedState,
+        entity: &SceneEntity,
+        npcs: &[NpcJson],
+    ) -> GameResult<Option<EntityIndex>> {
+        let position = Position { x: entity.x, y: entity.y };
+        let size = Size {
+            width: entity.width,
+            height: entity.height,
+        };
+        let index = match &entity.kind {
+            SceneEntityKind::Desk { desk_type, animation_id } => Some(self.add_desk(
+                position,
+                size,
+                Desk {
+                    desk_type: *desk_type,
+                    animation_id: *animation_id,
+                },
+            )),
+            SceneEntityKind::Npc { id } => match npcs.iter().find(|npc| npc.id == *id) {
+                Some(npc_data) => {
+                    let mut physics = generate_npc_physics();
+                    physics.position = position;
+                    physics.size = size;
+                    Some(self.spawn_npc(ctx, shared, npc_data, physics)?)
+                }
+                None => {
+                    eprintln!("The scene has NPC {} but the npcs config doesn't", id);
+                    None
+                }
+            },
+            SceneEntityKind::Prop { frame } => Some(self.add_entity(
+                Some(Physics::new(position, size, 0.0, graphics::Color::WHITE, None, None)),
+                None,
+                None,
+                None,
+                None,
+                Some(Prop { frame: frame.clone() }),
+            )),
+        };
+        if let (Some(index), Some(interactable)) = (index, &entity.interactable) {
+            self.set_interactable(index, interactable.clone());
+        }
+        Ok(index)
+    }
+
+    /// The office's current layout, as the scene file would store it.
+    pub fn scene_file(&self) -> SceneFile {
+        let mut entities = Vec::new();
+        for (index, physics) in self.physics_components.iter().enumerate() {
+            // NPCs out of the office keep their spot in it
+            let away = self.schedule_components[index]
+                .as_ref()
+                .and_then(|schedule| schedule.away.as_ref());
+            let physics = match physics.as_ref().or(away) {
+                Some(physics) => physics,
+                None => continue,
+            };
+            let kind = if let Some(desk) = &self.desk_components[index] {
+                SceneEntityKind::Desk {
+                    desk_type: desk.desk_type,
+                    animation_id: desk.animation_id,
+                }
+            } else if let Some(npc) = &self.npcs_components[index] {
+                SceneEntityKind::Npc { id: npc.id }
+            } else if let Some(prop) = &self.prop_components[index] {
+                SceneEntityKind::Prop { frame: prop.frame.clone() }
+            } else {
+                continue;
+            };
+            // Desks come with their monitor, only other objects are written out
+            let interactable = self.interactable_components[index]
+                .clone()
+                .filter(|interactable| self.desk_components[index].is_none() || *interactable != Interactable::monitor());
+            entities.push(SceneEntity {
+                x: physics.position.x,
+                y: physics.position.y,
+                width: physics.size.width,
+                height: physics.size.height,
+                kind,
+                interactable,
+            });
+        }
+        SceneFile { entities }
+    }
+
+    /// Removes an entity, leaving its index empty so the others keep theirs.
+    pub fn remove_entity(&mut self, index: EntityIndex) {
+        self.physics_components[index] = None;
+        self.npcs_components[index] = None;
+        self.npcs_interactions[index] = None;
+        self.desk_components[index] = None;
+        self.emitter_components[index] = None;
+        self.prop_components[index] = None;
+        self.schedule_components[index] = None;
+        self.light_components[index] = None;
+        self.interactable_components[index] = None;
+        self.door_components[index] = None;
+        if self.player_physics.current_focus == Some(index) {
+            self.player_physics.current_focus = None;
+        }
+    }
+
+    /// Whether Return does anything with the entity, see `input_system::begin_interaction`.
+    pub fn can_interact(&self, index: EntityIndex) -> bool {
+        let talks = self.npcs_components[index].is_some() && self.npcs_interactions[index].is_some();
+        talks || self.interactable_components[index].is_some()
+    }
+
+    /// The topmost entity whose collision box contains `position`. Doors are laid out in the
+    /// areas file, so they're left out.
+    pub fn entity_at(&self, position: &Position) -> Option<EntityIndex> {
+        self.physics_components
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| self.door_components[*index].is_none())
+            .filter_map(|(index, physics)| physics.map(|physics| (index, physics)))
+            .filter(|(_, physics)| {
+                (position.x - physics.position.x).abs() <= physics.size.w_half()
+                    && (position.y - physics.position.y).abs() <= physics.size.h_half()
+            })
+            .max_by(|(_, a), (_, b)| a.foot_y().total_cmp(&b.foot_y()))
+            .map(|(index, _)| index)
+    }
+
+    pub fn npcs_config(&self) -> ConfigHandle {
+        self.npcs_config
+    }
+
+    pub fn desk_types(&self) -> Vec<i32> {
+        let mut desk_types: Vec<i32> = self.office_sprite.desk_sprites.keys().copied().collect();
+        desk_types.sort_unstable();
+        desk_types
+    }
+
+    /// Frames of the office atlas that aren't desks.
+    pub fn prop_frames(&self) -> Vec<String> {
+        let mut frames: Vec<String> = self
+            .prop_sprite
+            .sprites
+            .keys()
+            .filter(|name| !name.starts_with("desk-"))
+            .cloned()
+            .collect();
+        frames.sort();
+        frames
+    }
+
+    /// Size of a prop's sprite in the world, drawn at twice the atlas' size.
+    pub fn prop_size(&self, frame: &str) -> (f32, f32) {
+        self.prop_sprite
+            .sprites
+            .get(frame)
+            .map_or((DESK_W, DESK_H), |sprite| (sprite.width * 2.0, sprite.height * 2.0))
+    }
+
+    /// The floor tile, which the editor snaps to.
+    pub fn tile_size(&self) -> Size {
+        match self.tiles.first() {
+            Some(tile) => Size {
+                width: tile.sprite.width,
+                height: tile.sprite.height,
+            },
+            None => Size { width: 1.0, height: 1.0 },
+        }
+    }
+
+    fn add_npcs(&mut self, ctx: &mut Context, shared: &mut SharedState) -> GameResult {
+        let config = load_npcs_config(&shared.asset_manager, self.npcs_config)?;
+        self.places = config.places;
+        for npc_data in config.npcs.iter() {
+            self.spawn_npc(ctx, shared, npc_data, npc_physics(npc_data))?;
+        }
+        Ok(())
+    }
+
+    fn spawn_npc(
+        &mut self,
+        ctx: &mut Context,
+        shared: &mut SharedState,
+        npc_data: &NpcJson,
+        physics: Physics,
+    ) -> GameResult<EntityIndex> {
+        let avatar = shared.asset_manager.load_image(ctx, &npc_data.avatar_path())?;
+        let index = self.add_entity(
+            Some(physics),
+            Some(npc_data.to_npc(avatar)),
+            npc_data.main_interaction.clone(),
+            None,
+            Some(
+                AudioEmitter::new(
+                    "chatter",
+                    AudioCategory::Voice,
+                    EmitterMode::Every { min_interval: 8.0, max_interval: 20.0 },
+                )
+                .attenuation(Attenuation::Smooth, 40.0, 350.0)
+                .volume(0.5)
+                .pitch(npc_data.voice_pitch),
+            ),
+            None,
+        );
+        self.schedule_components[index] = npc_data.to_schedule(&self.places);
+        Ok(index)
+    }
+
     fn add_desks(&mut self) {
         for animation_id in 2..6 {
-            let desk_type = rand::thread_rng().gen_range(0..=1);
-            let object_physics = Some(Physics::new(
+            let desk_type = rng::with_rng(|rng| rng.gen_range(0..=1));
+            self.add_desk(
                 Position {
                     x: 200.0,
                     y: animation_id as f32 * 130.0,
@@ -207,33 +1108,79 @@ impl GameState {
                     height: DESK_H,
                     width: DESK_W,
                 },
-                0.0,
-                graphics::Color::WHITE,
-                Some(Direction::Up),
-                None,
-            ));
-            self.add_entity(
-                object_physics,
-                None,
-                None,
-                Some(Desk {
+                Desk {
                     desk_type,
                     animation_id
-                }),
+                },
             );
         }
     }
 
+    fn add_desk(&mut self, position: Position, size: Size, desk: Desk) -> EntityIndex {
+        let object_physics = Some(Physics::new(
+            position,
+            size,
+            0.0,
+            graphics::Color::WHITE,
+            Some(Direction::Up),
+            None,
+        ));
+        let index = self.add_entity(
+            object_physics,
+            None,
+            None,
+            Some(desk),
+            Some(
+                AudioEmitter::new(
+                    "typing",
+                    AudioCategory::Sfx,
+                    EmitterMode::Every { min_interval: 2.0, max_interval: 6.0 },
+                )
+                .volume(0.6),
+            ),
+            None,
+        );
+        self.light_components[index] = Some(Light::monitor());
+        self.interactable_components[index] = Some(Interactable::monitor());
+        index
+    }
+
+    /// Makes an entity interactable, with its light switched on or off to match a toggle.
+    fn set_interactable(&mut self, index: EntityIndex, interactable: Interactable) {
+        if let (Some(on), Some(light)) = (interactable.is_on(), self.light_components[index].as_mut()) {
+            light.on = on;
+        }
+        self.interactable_components[index] = Some(interactable);
+    }
+
     fn add_entity(
         &mut self,
         physics: Option<Physics>,
         npc: Option<Npc>,
         interaction: Option<Interaction>,
         desk: Option<Desk>,
-    ) {
+        emitter: Option<AudioEmitter>,
+        prop: Option<Prop>,
+    ) -> EntityIndex {
         self.physics_components.push(physics);
         self.npcs_components.push(npc);
         self.npcs_interactions.push(interaction);
         self.desk_components.push(desk);
+        self.emitter_components.push(emitter);
+        self.prop_components.push(prop);
+        self.schedule_components.push(None);
+        self.light_components.push(None);
+        self.interactable_components.push(None);
+        self.door_components.push(None);
+        self.physics_components.len() - 1
+    }
+}
+
+/// An NPC standing where the config puts it, or somewhere random.
+fn npc_physics(npc_data: &NpcJson) -> Physics {
+    let mut physics = generate_npc_physics();
+    if let Some(position) = npc_data.spawn_position() {
+        physics.position = position;
     }
+    physics
 }
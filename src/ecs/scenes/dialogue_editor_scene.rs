@@ -0,0 +1,622 @@
+use std::collections::BTreeSet;
+
+use ggez::{
+    event::{KeyCode, KeyMods},
+    graphics::{self, Color, Rect},
+    input::keyboard,
+    Context, GameResult,
+};
+
+use super::{
+    dialogue_scene::DialogueScene,
+    scene::{Scene, SceneTransition},
+    shared_state::SharedState,
+};
+use crate::ecs::{
+    assets::asset_manager::{AssetEvent, ConfigHandle},
+    components::npc::Npc,
+    systems::input_system::interaction::Interaction,
+    ui::{
+        dialog_ui::{DialogUi, Speaker},
+        dialogue_graph::{draw_graph, layout_graph},
+    },
+    utils::{
+        constants::{NPCS_CONFIG, VIRTUAL_H},
+        npcs_json_loader::{read_npcs_config, save_npcs_config, validate_npcs, NpcsConfig},
+    },
+};
+
+const PANEL_W: f32 = 420.0;
+const MARGIN: f32 = 16.0;
+/// Room left at the bottom for the dialog box preview.
+const PREVIEW_H: f32 = 200.0;
+const PROBLEM_COLOR: Color = Color::new(1.0, 0.3, 0.3, 1.0);
+const DIM_COLOR: Color = Color::new(0.7, 0.7, 0.7, 1.0);
+/// Problems listed in the side panel, the rest are counted.
+const LISTED_PROBLEMS: usize = 8;
+/// Placeholder texts of a new branch, in every locale until they're edited.
+const NEW_DIALOG_TEXT: &str = "...";
+const NEW_OPTION_TEXT: &str = "- ...";
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum EditTarget {
+    /// The selected interaction's dialog key.
+    Dialog,
+    /// The parent's option that leads to the selected interaction.
+    Option,
+    /// What the dialog key says in the current locale.
+    DialogText,
+    /// What the option key says in the current locale.
+    OptionText,
+}
+
+impl EditTarget {
+    fn is_text(self) -> bool {
+        matches!(self, EditTarget::DialogText | EditTarget::OptionText)
+    }
+}
+
+struct TextEdit {
+    target: EditTarget,
+    text: String,
+    /// The letter of the shortcut that opened it, still to come as typed text.
+    shortcut: Option<char>,
+}
+
+/// A tool for the dialogue trees of `npcs_config.json`: each NPC's tree as a graph, editing
+/// of dialog and option keys and of their text in the current locale, adding and removing
+/// branches, what `validate_npcs` finds wrong, and the selected interaction shown in the
+/// real dialog box as it's edited.
+pub struct DialogueEditorScene {
+    npcs_config: ConfigHandle,
+    /// The config being edited, saved as a whole so the rest of it is kept.
+    config: NpcsConfig,
+    npc_index: usize,
+    /// Options picked from the root to the selected interaction.
+    selected: Vec<usize>,
+    editing: Option<TextEdit>,
+    /// The selected NPC as the dialog box shows it, none if its avatar couldn't load.
+    preview_npc: Option<Npc>,
+    dialog_ui: DialogUi,
+    /// Locales whose strings were changed, saved along with the config.
+    edited_locales: BTreeSet<String>,
+    /// Edited since the last save.
+    dirty: bool,
+    /// Escape was pressed once with unsaved changes.
+    leaving: bool,
+    status: String,
+}
+
+impl DialogueEditorScene {
+    pub fn new(ctx: &mut Context, shared: &mut SharedState) -> GameResult<Self> {
+        let npcs_config = shared.asset_manager.load_config(NPCS_CONFIG)?;
+        let config = read_npcs_config(&shared.asset_manager, npcs_config)?;
+        let mut scene = DialogueEditorScene {
+            npcs_config,
+            config,
+            npc_index: 0,
+            selected: Vec::new(),
+            editing: None,
+            preview_npc: None,
+            dialog_ui: DialogUi::new(&shared.ui_images),
+            edited_locales: BTreeSet::new(),
+            dirty: false,
+            leaving: false,
+            status: String::new(),
+        };
+        scene.select_npc(ctx, shared, 0);
+        Ok(scene)
+    }
+
+    fn select_npc(&mut self, ctx: &mut Context, shared: &mut SharedState, index: usize) {
+        self.npc_index = index;
+        self.selected.clear();
+        self.preview_npc = self.config.npcs.get(index).and_then(|npc_data| {
+            match shared.asset_manager.load_image(ctx, &npc_data.avatar_path()) {
+                Ok(avatar) => Some(npc_data.to_npc(avatar)),
+                Err(error) => {
+                    self.status = format!("No preview: {}", error);
+                    None
+                }
+            }
+        });
+        self.dialog_ui.restart();
+    }
+
+    fn root(&self) -> Option<&Interaction> {
+        self.config.npcs.get(self.npc_index)?.main_interaction.as_ref()
+    }
+
+    fn root_mut(&mut self) -> Option<&mut Interaction> {
+        self.config.npcs.get_mut(self.npc_index)?.main_interaction.as_mut()
+    }
+
+    fn selected_interaction(&self) -> Option<&Interaction> {
+        self.root()?.at_path(&self.selected)
+    }
+
+    fn select(&mut self, path: Vec<usize>) {
+        if self.root().is_some_and(|root| root.at_path(&path).is_some()) {
+            self.selected = path;
+            self.dialog_ui.restart();
+        }
+    }
+
+    fn problems(&self, shared: &SharedState) -> Vec<String> {
+        validate_npcs(&shared.asset_manager, &self.config)
+    }
+
+    fn dialog_key(&self) -> Option<String> {
+        self.selected_interaction().map(|interaction| interaction.dialog.clone())
+    }
+
+    /// Key of the option leading to the selected interaction, empty if the parent lacks it.
+    fn option_key(&self) -> Option<String> {
+        self.selected.split_last().and_then(|(index, parent_path)| {
+            let parent = self.root()?.at_path(parent_path)?;
+            Some(parent.options.as_ref()?.get(*index).cloned().unwrap_or_default())
+        })
+    }
+
+    fn start_editing(&mut self, shared: &SharedState, target: EditTarget) {
+        let key = match target {
+            EditTarget::Dialog | EditTarget::DialogText => self.dialog_key(),
+            EditTarget::Option | EditTarget::OptionText => self.option_key(),
+        };
+        let text = match key {
+            Some(key) if target.is_text() && key.is_empty() => {
+                self.status = "Give it a key first, Shift+Return or Shift+Tab".to_string();
+                return;
+            }
+            Some(key) if target.is_text() => {
+                let localization = &shared.localization;
+                localization.text(localization.locale(), &key).unwrap_or_default().to_string()
+            }
+            Some(key) => key,
+            None => {
+                if matches!(target, EditTarget::Option | EditTarget::OptionText) {
+                    self.status = "The start has no option leading to it".to_string();
+                }
+                return;
+            }
+        };
+        self.editing = Some(TextEdit {
+            target,
+            text,
+            shortcut: None,
+        });
+    }
+
+    fn finish_editing(&mut self, shared: &mut SharedState) {
+        let edit = match self.editing.take() {
+            Some(edit) => edit,
+            None => return,
+        };
+        let text = edit.text.trim().to_string();
+        if edit.target.is_text() {
+            let key = match edit.target {
+                EditTarget::DialogText => self.dialog_key(),
+                _ => self.option_key(),
+            };
+            if let Some(key) = key {
+                let locale = shared.localization.locale().to_string();
+                shared.localization.set_text(&locale, &key, text);
+                self.edited_locales.insert(locale);
+                self.dirty = true;
+                self.dialog_ui.restart();
+            }
+            return;
+        }
+
+        let selected = self.selected.clone();
+        let root = match self.root_mut() {
+            Some(root) => root,
+            None => return,
+        };
+
+        match edit.target {
+            EditTarget::Dialog => {
+                if let Some(interaction) = root.at_path_mut(&selected) {
+                    interaction.dialog = text;
+                }
+            }
+            EditTarget::Option => {
+                if let Some((index, parent_path)) = selected.split_last() {
+                    if let Some(parent) = root.at_path_mut(parent_path) {
+                        let options = parent.options.get_or_insert_with(Vec::new);
+                        // Fills in options missing before this one, which the validation reports
+                        while options.len() <= *index {
+                            options.push(String::new());
+                        }
+                        options[*index] = text;
+                    }
+                }
+            }
+            EditTarget::DialogText | EditTarget::OptionText => (),
+        }
+        self.dirty = true;
+    }
+
+    /// A key starting with `prefix` that no locale and no dialogue uses yet, e.g.
+    /// `dialog.julian_3`.
+    fn unused_key(&self, shared: &SharedState, prefix: &str) -> String {
+        let name = self
+            .config
+            .npcs
+            .get(self.npc_index)
+            .map_or_else(|| "npc".to_string(), |npc| npc.name.to_lowercase().replace(' ', "_"));
+        let used: Vec<String> = self
+            .config
+            .npcs
+            .iter()
+            .filter_map(|npc| npc.main_interaction.as_ref())
+            .flat_map(Interaction::text_keys)
+            .collect();
+        (1..)
+            .map(|number| format!("{}.{}_{}", prefix, name, number))
+            .find(|key| !shared.localization.contains(key) && !used.contains(key))
+            .unwrap_or_default()
+    }
+
+    /// Adds an option with its sub interaction under the selected interaction, with new keys
+    /// holding placeholder texts in every locale, and starts editing the new line's text.
+    fn add_branch(&mut self, shared: &mut SharedState) {
+        let dialog_key = self.unused_key(shared, "dialog");
+        let option_key = self.unused_key(shared, "option");
+        let selected = self.selected.clone();
+        let npc = match self.config.npcs.get_mut(self.npc_index) {
+            Some(npc) => npc,
+            None => return,
+        };
+        let mut new_keys = vec![(dialog_key.clone(), NEW_DIALOG_TEXT)];
+
+        // A tree is started at its root, which has no option leading to it
+        let interaction = match npc.main_interaction.as_mut() {
+            Some(root) => match root.at_path_mut(&selected) {
+                Some(interaction) => interaction,
+                None => return,
+            },
+            None => {
+                npc.main_interaction = Some(Interaction::new(None, None, dialog_key));
+                self.selected = Vec::new();
+                self.add_keys(shared, &new_keys);
+                self.start_editing(shared, EditTarget::DialogText);
+                self.status = "Started the dialogue".to_string();
+                return;
+            }
+        };
+        let sub_interactions = interaction.sub_interactions.get_or_insert_with(Vec::new);
+        let index = sub_interactions.len();
+        sub_interactions.push(Interaction::new(None, None, dialog_key));
+        let options = interaction.options.get_or_insert_with(Vec::new);
+        if options.len() == index {
+            options.push(option_key.clone());
+            new_keys.push((option_key, NEW_OPTION_TEXT));
+        }
+        let mut path = selected;
+        path.push(index);
+        self.selected = path;
+        self.add_keys(shared, &new_keys);
+        self.start_editing(shared, EditTarget::DialogText);
+        self.status = "Added a branch".to_string();
+    }
+
+    fn add_keys(&mut self, shared: &mut SharedState, keys: &[(String, &str)]) {
+        let locales = shared.localization.locales().to_vec();
+        for locale in locales {
+            for (key, text) in keys {
+                shared.localization.set_text(&locale, key, text.to_string());
+            }
+            self.edited_locales.insert(locale);
+        }
+        self.dirty = true;
+    }
+
+    /// Removes the selected interaction and the option leading to it.
+    fn remove_branch(&mut self) {
+        let (index, parent_path) = match self.selected.split_last() {
+            Some((index, parent_path)) => (*index, parent_path.to_vec()),
+            None => {
+                self.status = "The start can't be removed".to_string();
+                return;
+            }
+        };
+        if let Some(parent) = self.root_mut().and_then(|root| root.at_path_mut(&parent_path)) {
+            if let Some(sub_interactions) = parent.sub_interactions.as_mut() {
+                if index < sub_interactions.len() {
+                    sub_interactions.remove(index);
+                }
+            }
+            if let Some(options) = parent.options.as_mut() {
+                if index < options.len() {
+                    options.remove(index);
+                }
+            }
+            if parent.sub_interactions.as_ref().is_some_and(Vec::is_empty) {
+                parent.sub_interactions = None;
+            }
+            if parent.options.as_ref().is_some_and(Vec::is_empty) {
+                parent.options = None;
+            }
+        }
+        self.selected = parent_path;
+        self.dirty = true;
+        self.status = "Removed a branch".to_string();
+    }
+
+    /// Saves the config and the locales whose strings changed.
+    fn save(&mut self, shared: &SharedState) {
+        let saved = save_npcs_config(&shared.asset_manager, &self.config).and_then(|()| {
+            self.edited_locales
+                .iter()
+                .try_for_each(|locale| shared.localization.save(&shared.asset_manager, locale))
+        });
+        self.status = match saved {
+            Ok(()) => {
+                self.dirty = false;
+                self.edited_locales.clear();
+                match self.problems(shared).len() {
+                    0 => format!("Saved {}", shared.asset_manager.source().display(NPCS_CONFIG)),
+                    count => format!("Saved with {} problems", count),
+                }
+            }
+            Err(error) => format!("Couldn't save: {}", error),
+        };
+    }
+
+    /// Puts the strings back as their files have them, when leaving without saving.
+    fn revert_locales(&mut self, shared: &mut SharedState) {
+        for locale in std::mem::take(&mut self.edited_locales) {
+            if let Err(error) = shared.localization.revert(&shared.asset_manager, &locale) {
+                eprintln!("Couldn't revert the {} strings: {}", locale, error);
+            }
+        }
+    }
+
+    fn editing_key_down(&mut self, shared: &mut SharedState, key: KeyCode) {
+        match key {
+            KeyCode::Return | KeyCode::NumpadEnter => self.finish_editing(shared),
+            KeyCode::Escape => self.editing = None,
+            KeyCode::Back => {
+                if let Some(edit) = self.editing.as_mut() {
+                    edit.text.pop();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn panel_lines(&self, shared: &SharedState) -> Vec<(String, Color)> {
+        let mut lines = Vec::new();
+        let mut line = |text: String, color: Color| lines.push((text, color));
+
+        match self.config.npcs.get(self.npc_index) {
+            Some(npc) => line(
+                format!("NPC {}/{}: {} (id {})", self.npc_index + 1, self.config.npcs.len(), npc.name, npc.id),
+                Color::WHITE,
+            ),
+            None => line("No NPCs".to_string(), Color::WHITE),
+        }
+        line(String::new(), Color::WHITE);
+
+        match (&self.editing, self.selected_interaction()) {
+            (Some(edit), _) => {
+                let locale = shared.localization.locale();
+                let label = match edit.target {
+                    EditTarget::Dialog => "Dialog key".to_string(),
+                    EditTarget::Option => "Option key".to_string(),
+                    EditTarget::DialogText => format!("Dialog text ({})", locale),
+                    EditTarget::OptionText => format!("Option text ({})", locale),
+                };
+                line(format!("{}:", label), DIM_COLOR);
+                line(format!("{}_", edit.text), Color::WHITE);
+                if !edit.target.is_text() {
+                    line(format!("= {}", shared.localization.tr(edit.text.trim())), DIM_COLOR);
+                }
+            }
+            (None, Some(interaction)) => {
+                if let Some((index, parent_path)) = self.selected.split_last() {
+                    let option = self
+                        .root()
+                        .and_then(|root| root.at_path(parent_path))
+                        .and_then(|parent| parent.options.as_ref()?.get(*index).cloned());
+                    let option = option.as_deref().unwrap_or("(missing)");
+                    line(format!("Option: {} = {}", option, shared.localization.tr(option)), DIM_COLOR);
+                }
+                line(format!("Dialog: {}", interaction.dialog), Color::WHITE);
+                line(format!("= {}", shared.localization.tr(&interaction.dialog)), DIM_COLOR);
+                for problem in interaction.own_problems("here") {
+                    line(problem, PROBLEM_COLOR);
+                }
+            }
+            (None, None) => line("No dialogue, N to start one".to_string(), DIM_COLOR),
+        }
+        line(String::new(), Color::WHITE);
+
+        let problems = self.problems(shared);
+        if problems.is_empty() {
+            line("No problems".to_string(), DIM_COLOR);
+        } else {
+            line(format!("{} problems:", problems.len()), PROBLEM_COLOR);
+            for problem in problems.iter().take(LISTED_PROBLEMS) {
+                line(problem.clone(), PROBLEM_COLOR);
+            }
+            if problems.len() > LISTED_PROBLEMS {
+                line(format!("...and {} more", problems.len() - LISTED_PROBLEMS), PROBLEM_COLOR);
+            }
+        }
+        line(String::new(), Color::WHITE);
+
+        for help in [
+            "Arrows: move through the tree",
+            "PageUp/PageDown: other NPC",
+            "Return: edit dialog text, Tab: edit option text",
+            "Shift: edit the key instead",
+            "N: add branch, Delete: remove branch",
+            "P: play from here",
+            "Ctrl+S: save, Escape: leave",
+        ] {
+            line(help.to_string(), DIM_COLOR);
+        }
+        if !self.status.is_empty() {
+            line(String::new(), Color::WHITE);
+            line(self.status.clone(), Color::WHITE);
+        }
+        lines
+    }
+}
+
+impl Scene for DialogueEditorScene {
+    fn update(&mut self, ctx: &mut Context, shared: &mut SharedState) -> GameResult<SceneTransition> {
+        // The dialog box shows the text being typed before it's committed
+        let mut preview = self.selected_interaction().cloned();
+        if let (Some(preview), Some(edit)) = (preview.as_mut(), &self.editing) {
+            // Text that isn't a key is shown as it is
+            if matches!(edit.target, EditTarget::Dialog | EditTarget::DialogText) {
+                preview.dialog = edit.text.trim().to_string();
+            }
+        }
+        if let (Some(npc), Some(interaction)) = (&self.preview_npc, preview) {
+            self.dialog_ui.sync(&Speaker::npc(npc), &interaction, &shared.localization);
+            self.dialog_ui.update(ggez::timer::delta(ctx).as_secs_f32());
+        }
+        Ok(SceneTransition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, shared: &mut SharedState) -> GameResult {
+        graphics::clear(ctx, Color::from_rgb(30, 39, 46));
+        let screen = shared.viewport.virtual_size;
+        let asset_manager = &shared.asset_manager;
+
+        if let Some(root) = self.root() {
+            let area = Rect::new(
+                MARGIN,
+                MARGIN,
+                screen.width - PANEL_W - 2.0 * MARGIN,
+                screen.height.min(VIRTUAL_H) - PREVIEW_H - MARGIN,
+            );
+            draw_graph(ctx, asset_manager, &shared.localization, &layout_graph(root), &self.selected, area)?;
+        }
+
+        let font = asset_manager.ui_font();
+        let line_height = font.line_height(ctx);
+        let mut y = MARGIN;
+        for (text, color) in self.panel_lines(shared) {
+            font.draw_text(ctx, asset_manager, &text, [screen.width - PANEL_W, y], color)?;
+            y += line_height;
+        }
+
+        if self.preview_npc.is_some() && self.selected_interaction().is_some() {
+            self.dialog_ui.draw(ctx, asset_manager, &screen)?;
+        }
+        Ok(())
+    }
+
+    fn key_down(
+        &mut self,
+        ctx: &mut Context,
+        shared: &mut SharedState,
+        key: KeyCode,
+    ) -> GameResult<SceneTransition> {
+        if self.editing.is_some() {
+            self.editing_key_down(shared, key);
+            return Ok(SceneTransition::None);
+        }
+        if key != KeyCode::Escape {
+            self.leaving = false;
+        }
+
+        let ctrl = keyboard::is_mod_active(ctx, KeyMods::CTRL);
+        let shift = keyboard::is_mod_active(ctx, KeyMods::SHIFT);
+        match key {
+            KeyCode::Escape if self.dirty && !self.leaving => {
+                self.leaving = true;
+                self.status = "Unsaved changes, Escape again to leave".to_string();
+            }
+            KeyCode::Escape => {
+                self.revert_locales(shared);
+                return Ok(SceneTransition::Pop);
+            }
+            KeyCode::S if ctrl => self.save(shared),
+            KeyCode::PageUp | KeyCode::PageDown if !self.config.npcs.is_empty() => {
+                let step = if key == KeyCode::PageUp { -1 } else { 1 };
+                let index = (self.npc_index as i32 + step).rem_euclid(self.config.npcs.len() as i32);
+                self.select_npc(ctx, shared, index as usize);
+            }
+            KeyCode::Left => {
+                let mut path = self.selected.clone();
+                path.pop();
+                self.select(path);
+            }
+            KeyCode::Right => {
+                let mut path = self.selected.clone();
+                path.push(0);
+                self.select(path);
+            }
+            KeyCode::Up | KeyCode::Down => {
+                if let Some((index, parent_path)) = self.selected.split_last() {
+                    let index = if key == KeyCode::Up { index.checked_sub(1) } else { Some(index + 1) };
+                    if let Some(index) = index {
+                        let mut path = parent_path.to_vec();
+                        path.push(index);
+                        self.select(path);
+                    }
+                }
+            }
+            KeyCode::Return | KeyCode::NumpadEnter if shift => self.start_editing(shared, EditTarget::Dialog),
+            KeyCode::Return | KeyCode::NumpadEnter => self.start_editing(shared, EditTarget::DialogText),
+            KeyCode::Tab if shift => self.start_editing(shared, EditTarget::Option),
+            KeyCode::Tab => self.start_editing(shared, EditTarget::OptionText),
+            KeyCode::N | KeyCode::Insert => {
+                self.add_branch(shared);
+                if let (KeyCode::N, Some(edit)) = (key, self.editing.as_mut()) {
+                    edit.shortcut = Some('n');
+                }
+            }
+            KeyCode::Delete => self.remove_branch(),
+            KeyCode::P => {
+                if let (Some(npc), Some(interaction)) = (&self.preview_npc, self.selected_interaction()) {
+                    let scene = DialogueScene::new(shared, Speaker::npc(npc), interaction.clone());
+                    return Ok(SceneTransition::Push(Box::new(scene)));
+                }
+            }
+            _ => (),
+        }
+        Ok(SceneTransition::None)
+    }
+
+    /// Shortcuts are letters, and dialog keys and texts are typed.
+    fn wants_raw_keys(&self) -> bool {
+        true
+    }
+
+    fn text_input(&mut self, _ctx: &mut Context, _shared: &mut SharedState, ch: char) {
+        if let Some(edit) = self.editing.as_mut() {
+            // Only the character right after the shortcut can be it
+            let shortcut = edit.shortcut.take();
+            if !ch.is_control() && !shortcut.is_some_and(|shortcut| ch.eq_ignore_ascii_case(&shortcut)) {
+                edit.text.push(ch);
+            }
+        }
+    }
+
+    /// Picks up edits made to the file by hand, unless there are unsaved ones here.
+    fn asset_event(&mut self, ctx: &mut Context, shared: &mut SharedState, event: AssetEvent) -> GameResult {
+        if event == AssetEvent::ConfigReloaded(self.npcs_config) && !self.dirty {
+            // A file that doesn't parse leaves what's shown here as it was
+            self.config = match read_npcs_config(&shared.asset_manager, self.npcs_config) {
+                Ok(config) => config,
+                Err(error) => {
+                    self.status = format!("Couldn't reload: {}", error);
+                    eprintln!("Couldn't reload the NPCs in the dialogue editor: {}", error);
+                    return Ok(());
+                }
+            };
+            let selected = std::mem::take(&mut self.selected);
+            let npc_index = self.npc_index.min(self.config.npcs.len().saturating_sub(1));
+            self.select_npc(ctx, shared, npc_index);
+            self.select(selected);
+        }
+        Ok(())
+    }
+}
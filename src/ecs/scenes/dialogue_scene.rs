@@ -0,0 +1,76 @@
+use ggez::{event::KeyCode, Context, GameResult};
+
+use super::{
+    scene::{Scene, SceneTransition},
+    shared_state::SharedState,
+};
+use crate::ecs::{
+    systems::input_system::{input_system, interaction::Interaction},
+    ui::dialog_ui::{DialogUi, Speaker},
+};
+
+/// A conversation with an NPC, or what an object has to say, over the office.
+pub struct DialogueScene {
+    speaker: Speaker,
+    interaction: Interaction,
+    dialog_ui: DialogUi,
+}
+
+impl DialogueScene {
+    pub fn new(shared: &SharedState, speaker: Speaker, interaction: Interaction) -> Self {
+        DialogueScene {
+            speaker,
+            interaction,
+            dialog_ui: DialogUi::new(&shared.ui_images),
+        }
+    }
+}
+
+impl Scene for DialogueScene {
+    fn update(&mut self, ctx: &mut Context, shared: &mut SharedState) -> GameResult<SceneTransition> {
+        self.dialog_ui
+            .sync(&self.speaker, &self.interaction, &shared.localization);
+        let revealed = self.dialog_ui.update(ggez::timer::delta(ctx).as_secs_f32());
+        if let Some(voice_pitch) = self.speaker.voice_pitch {
+            shared.audio_system.dialog_blip(ctx, revealed, voice_pitch);
+        }
+        shared.audio_system.play_music(ctx, "dialogue");
+        Ok(SceneTransition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, shared: &mut SharedState) -> GameResult {
+        self.dialog_ui
+            .draw(ctx, &shared.asset_manager, &shared.viewport.virtual_size)
+    }
+
+    fn key_down(
+        &mut self,
+        ctx: &mut Context,
+        shared: &mut SharedState,
+        key: KeyCode,
+    ) -> GameResult<SceneTransition> {
+        if key == KeyCode::Return && self.dialog_ui.advance() {
+            return Ok(SceneTransition::None);
+        }
+        // The options only show up once the text is done
+        if matches!(key, KeyCode::Up | KeyCode::Down) && !self.dialog_ui.text_finished() {
+            return Ok(SceneTransition::None);
+        }
+
+        let before = Some(self.interaction.clone());
+        let after = input_system::interaction_input_handler(&self.interaction, key);
+        shared.play_ui_sound(ctx, input_system::interaction_sound(&before, &after));
+
+        Ok(match after {
+            Some(interaction) => {
+                self.interaction = interaction;
+                SceneTransition::None
+            }
+            None => SceneTransition::Pop,
+        })
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,72 @@
+use ggez::{
+    event::KeyCode,
+    graphics::{self, Color, DrawMode, DrawParam, Rect},
+    Context, GameResult,
+};
+
+use super::{
+    scene::{Scene, SceneTransition},
+    settings_scene::SettingsScene,
+    shared_state::SharedState,
+    title_scene::TitleScene,
+};
+use crate::ecs::ui::menu_ui::{MenuInput, MenuUi};
+
+const PAUSE_ITEMS: [&str; 4] = ["menu.resume", "menu.settings", "menu.quit_to_title", "menu.quit"];
+
+/// Menu over the frozen office.
+pub struct PauseScene {
+    menu: MenuUi,
+}
+
+impl PauseScene {
+    pub fn new(shared: &mut SharedState) -> Self {
+        PauseScene {
+            menu: MenuUi::new(&shared.ui_images),
+        }
+    }
+}
+
+impl Scene for PauseScene {
+    fn update(&mut self, _ctx: &mut Context, shared: &mut SharedState) -> GameResult<SceneTransition> {
+        let localization = &shared.localization;
+        self.menu.set_title(localization.tr("menu.paused"));
+        self.menu
+            .set_items(PAUSE_ITEMS.iter().map(|key| localization.tr(key).to_string()).collect());
+        Ok(SceneTransition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, shared: &mut SharedState) -> GameResult {
+        let size = shared.viewport.virtual_size;
+        let dim = graphics::Mesh::new_rectangle(
+            ctx,
+            DrawMode::fill(),
+            Rect::new(0.0, 0.0, size.width, size.height),
+            Color::new(0.0, 0.0, 0.0, 0.5),
+        )?;
+        graphics::draw(ctx, &dim, DrawParam::default())?;
+        self.menu.draw(ctx, &shared.asset_manager, &size)
+    }
+
+    fn key_down(
+        &mut self,
+        ctx: &mut Context,
+        shared: &mut SharedState,
+        key: KeyCode,
+    ) -> GameResult<SceneTransition> {
+        let input = self.menu.key_down(key);
+        shared.play_ui_sound(ctx, input.sound());
+
+        Ok(match input {
+            MenuInput::Chosen(0) | MenuInput::Back => SceneTransition::Pop,
+            MenuInput::Chosen(1) => SceneTransition::Push(Box::new(SettingsScene::new(shared))),
+            MenuInput::Chosen(2) => SceneTransition::Reset(Box::new(TitleScene::new(shared))),
+            MenuInput::Chosen(3) => SceneTransition::Quit,
+            _ => SceneTransition::None,
+        })
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,72 @@
+use ggez::{
+    event::{KeyCode, MouseButton},
+    Context, GameResult,
+};
+
+use super::shared_state::SharedState;
+use crate::ecs::{assets::asset_manager::AssetEvent, systems::physics_system::physics::Position};
+
+/// What the scene stack should do after a scene handled an update or a key.
+pub enum SceneTransition {
+    None,
+    /// Puts a scene on top of the current one.
+    Push(Box<dyn Scene>),
+    /// Removes the current scene, going back to the one below.
+    Pop,
+    /// Swaps the current scene for another one.
+    Replace(Box<dyn Scene>),
+    /// Drops every scene and starts over from the given one.
+    Reset(Box<dyn Scene>),
+    Quit,
+}
+
+/// One screen of the game: the title, the office, a menu over it...
+///
+/// Only the scene on top of the stack is updated and gets input. It's drawn over the
+/// scenes below it when it's an overlay, and hides them otherwise.
+pub trait Scene {
+    fn update(&mut self, ctx: &mut Context, shared: &mut SharedState) -> GameResult<SceneTransition>;
+
+    /// Draws into the virtual canvas, in virtual screen coordinates.
+    fn draw(&mut self, ctx: &mut Context, shared: &mut SharedState) -> GameResult;
+
+    fn key_down(
+        &mut self,
+        ctx: &mut Context,
+        shared: &mut SharedState,
+        key: KeyCode,
+    ) -> GameResult<SceneTransition>;
+
+    fn is_overlay(&self) -> bool {
+        false
+    }
+
+    /// Keys normally reach scenes translated through the key bindings, e.g. a key bound
+    /// to interacting arrives as `Return`. A scene can ask for the keys as pressed instead.
+    fn wants_raw_keys(&self) -> bool {
+        false
+    }
+
+    /// A character typed on the keyboard, for scenes with text fields.
+    fn text_input(&mut self, _ctx: &mut Context, _shared: &mut SharedState, _ch: char) {}
+
+    /// Mouse input, at positions in virtual screen coordinates.
+    fn mouse_down(&mut self, _ctx: &mut Context, _shared: &mut SharedState, _button: MouseButton, _position: Position) {}
+
+    fn mouse_up(&mut self, _ctx: &mut Context, _shared: &mut SharedState, _button: MouseButton, _position: Position) {}
+
+    fn mouse_motion(&mut self, _ctx: &mut Context, _shared: &mut SharedState, _position: Position) {}
+
+    /// Called after the window, and so the viewport, changed size.
+    fn resize(&mut self, _shared: &SharedState) {}
+
+    /// Called for every asset reloaded from disk, on every scene of the stack.
+    fn asset_event(
+        &mut self,
+        _ctx: &mut Context,
+        _shared: &mut SharedState,
+        _event: AssetEvent,
+    ) -> GameResult {
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+use ggez::{
+    event::{self, KeyCode, KeyMods, MouseButton},
+    graphics::{self, Color, DrawMode, DrawParam, Rect},
+    Context, GameError, GameResult,
+};
+
+use super::{
+    dialogue_editor_scene::DialogueEditorScene,
+    scene::{Scene, SceneTransition},
+    shared_state::SharedState,
+    title_scene::TitleScene,
+};
+use crate::ecs::{
+    assets::{asset_manager::AssetEvent, resources::ResourceSource},
+    game_state::GameState,
+    systems::physics_system::physics::Position,
+    utils::{
+        cli::{CliOptions, StartScene},
+        constants::SCENE_FADE_DURATION,
+        settings::Settings,
+    },
+};
+
+enum Fade {
+    None,
+    /// Darkening the screen, the transition happens once it's black.
+    Out { transition: SceneTransition, elapsed: f32 },
+    In { elapsed: f32 },
+}
+
+/// The game's `EventHandler`: a stack of scenes sharing one `SharedState`. Transitions
+/// between full screen scenes fade through black, overlays come and go right away.
+pub struct SceneStack {
+    shared: SharedState,
+    scenes: Vec<Box<dyn Scene>>,
+    fade: Fade,
+}
+
+impl SceneStack {
+    pub fn new(
+        ctx: &mut Context,
+        settings: Settings,
+        resources: ResourceSource,
+        options: &CliOptions,
+        audio_enabled: bool,
+    ) -> GameResult<Self> {
+        let mut shared = SharedState::new(ctx, settings, resources, options, audio_enabled)?;
+        let first_scene: Box<dyn Scene> = match options.start_scene {
+            StartScene::Title => Box::new(TitleScene::new(&mut shared)),
+            StartScene::Office => Box::new(GameState::new(ctx, &mut shared)?),
+            StartScene::Dialogues => Box::new(DialogueEditorScene::new(ctx, &mut shared)?),
+        };
+        Ok(SceneStack {
+            shared,
+            scenes: vec![first_scene],
+            fade: Fade::In { elapsed: 0.0 },
+        })
+    }
+
+    fn request(&mut self, ctx: &mut Context, transition: SceneTransition) {
+        if !matches!(self.fade, Fade::None) {
+            return;
+        }
+
+        let fades = match &transition {
+            SceneTransition::None => return,
+            SceneTransition::Push(scene) => !scene.is_overlay(),
+            SceneTransition::Pop => self.scenes.last().is_some_and(|scene| !scene.is_overlay()),
+            SceneTransition::Replace(_) | SceneTransition::Reset(_) => true,
+            SceneTransition::Quit => false,
+        };
+
+        if fades {
+            self.fade = Fade::Out {
+                transition,
+                elapsed: 0.0,
+            };
+        } else {
+            self.apply(ctx, transition);
+        }
+    }
+
+    fn apply(&mut self, ctx: &mut Context, transition: SceneTransition) {
+        match transition {
+            SceneTransition::None => (),
+            SceneTransition::Push(scene) => self.scenes.push(scene),
+            SceneTransition::Pop => {
+                self.scenes.pop();
+            }
+            SceneTransition::Replace(scene) => {
+                self.scenes.pop();
+                self.scenes.push(scene);
+            }
+            SceneTransition::Reset(scene) => {
+                self.scenes.clear();
+                self.scenes.push(scene);
+            }
+            SceneTransition::Quit => event::quit(ctx),
+        }
+
+        if self.scenes.is_empty() {
+            event::quit(ctx);
+        }
+    }
+
+    fn update_fade(&mut self, ctx: &mut Context, dt: f32) {
+        self.fade = match std::mem::replace(&mut self.fade, Fade::None) {
+            Fade::None => Fade::None,
+            Fade::Out { transition, elapsed } if elapsed + dt >= SCENE_FADE_DURATION => {
+                self.apply(ctx, transition);
+                Fade::In { elapsed: 0.0 }
+            }
+            Fade::Out { transition, elapsed } => Fade::Out {
+                transition,
+                elapsed: elapsed + dt,
+            },
+            Fade::In { elapsed } if elapsed + dt >= SCENE_FADE_DURATION => Fade::None,
+            Fade::In { elapsed } => Fade::In { elapsed: elapsed + dt },
+        };
+    }
+
+    /// Opacity of the black layer drawn over everything.
+    fn fade_alpha(&self) -> f32 {
+        let alpha = match &self.fade {
+            Fade::None => 0.0,
+            Fade::Out { elapsed, .. } => elapsed / SCENE_FADE_DURATION,
+            Fade::In { elapsed } => 1.0 - elapsed / SCENE_FADE_DURATION,
+        };
+        alpha.clamp(0.0, 1.0)
+    }
+
+    fn apply_asset_event(&mut self, ctx: &mut Context, event: AssetEvent) -> GameResult {
+        if let AssetEvent::ConfigReloaded(config) = event {
+            // A broken locale keeps the table it had before
+            if let Err(error) = self.shared.localization.reload(&self.shared.asset_manager, config) {
+                eprintln!("Couldn't reload the locale: {}", error);
+            }
+        }
+        for scene in self.scenes.iter_mut() {
+            scene.asset_event(ctx, &mut self.shared, event)?;
+        }
+        Ok(())
+    }
+}
+
+impl event::EventHandler<GameError> for SceneStack {
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        // A broken file is reported and whatever was loaded before stays, the game goes on
+        for event in self.shared.asset_manager.poll_changes(ctx) {
+            if let Err(error) = self.apply_asset_event(ctx, event) {
+                eprintln!("Couldn't apply {:?}: {}", event, error);
+            }
+        }
+
+        let dt = ggez::timer::delta(ctx).as_secs_f32();
+        self.update_fade(ctx, dt);
+
+        if let Some(scene) = self.scenes.last_mut() {
+            let transition = scene.update(ctx, &mut self.shared)?;
+            self.request(ctx, transition);
+        }
+
+        self.shared.audio_system.update(ctx);
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        self.shared.viewport.begin(ctx)?;
+        graphics::clear(ctx, Color::BLACK);
+
+        // Start from the topmost scene that hides everything under it
+        let first_visible = self
+            .scenes
+            .iter()
+            .rposition(|scene| !scene.is_overlay())
+            .unwrap_or(0);
+        for scene in self.scenes[first_visible..].iter_mut() {
+            scene.draw(ctx, &mut self.shared)?;
+        }
+
+        let alpha = self.fade_alpha();
+        if alpha > 0.0 {
+            let size = self.shared.viewport.virtual_size;
+            let rect = graphics::Mesh::new_rectangle(
+                ctx,
+                DrawMode::fill(),
+                Rect::new(0.0, 0.0, size.width, size.height),
+                Color::new(0.0, 0.0, 0.0, alpha),
+            )?;
+            graphics::draw(ctx, &rect, DrawParam::default())?;
+        }
+
+        self.shared.viewport.finish(ctx)?;
+        graphics::present(ctx)
+    }
+
+    fn key_down_event(&mut self, ctx: &mut Context, key: KeyCode, _mods: KeyMods, repeat: bool) {
+        match key {
+            KeyCode::F11 if !repeat => {
+                self.shared.toggle_fullscreen(ctx);
+                self.shared.save_settings();
+            }
+            KeyCode::F3 if !repeat => self.shared.debug_overlay = !self.shared.debug_overlay,
+            KeyCode::F2 if !repeat => {
+                self.shared.change_locale(ctx, 1);
+                self.shared.save_settings();
+            }
+            // Input waits for the screen to come back from black
+            _ if !matches!(self.fade, Fade::None) => (),
+            _ => {
+                if let Some(scene) = self.scenes.last_mut() {
+                    let key = if scene.wants_raw_keys() {
+                        key
+                    } else {
+                        self.shared.settings.controls.translate(key)
+                    };
+                    match scene.key_down(ctx, &mut self.shared, key) {
+                        Ok(transition) => self.request(ctx, transition),
+                        Err(error) => eprintln!("Couldn't handle {:?}: {}", key, error),
+                    }
+                }
+            }
+        }
+    }
+
+    fn text_input_event(&mut self, ctx: &mut Context, ch: char) {
+        if !matches!(self.fade, Fade::None) {
+            return;
+        }
+        if let Some(scene) = self.scenes.last_mut() {
+            scene.text_input(ctx, &mut self.shared, ch);
+        }
+    }
+
+    fn mouse_button_down_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        if !matches!(self.fade, Fade::None) {
+            return;
+        }
+        let position = self.shared.viewport.window_to_virtual(&Position { x, y });
+        if let Some(scene) = self.scenes.last_mut() {
+            scene.mouse_down(ctx, &mut self.shared, button, position);
+        }
+    }
+
+    fn mouse_button_up_event(&mut self, ctx: &mut Context, button: MouseButton, x: f32, y: f32) {
+        let position = self.shared.viewport.window_to_virtual(&Position { x, y });
+        if let Some(scene) = self.scenes.last_mut() {
+            scene.mouse_up(ctx, &mut self.shared, button, position);
+        }
+    }
+
+    fn mouse_motion_event(&mut self, ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        let position = self.shared.viewport.window_to_virtual(&Position { x, y });
+        if let Some(scene) = self.scenes.last_mut() {
+            scene.mouse_motion(ctx, &mut self.shared, position);
+        }
+    }
+
+    fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32) {
+        if let Err(error) = self.shared.viewport.resize(ctx, width, height) {
+            eprintln!("Couldn't resize the viewport: {}", error);
+        }
+        for scene in self.scenes.iter_mut() {
+            scene.resize(&self.shared);
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use ggez::{
+    event::KeyCode,
+    graphics::{self, Color, DrawMode, DrawParam, Rect},
+    Context, GameResult,
+};
+use strum::IntoEnumIterator;
+
+use super::{
+    scene::{Scene, SceneTransition},
+    shared_state::SharedState,
+};
+use crate::ecs::{
+    systems::{audio_system::audio::AudioCategory, input_system::input_system::InteractionSound},
+    ui::menu_ui::{MenuInput, MenuUi},
+    utils::{
+        constants::RESOLUTIONS,
+        settings::{key_from_name, key_label, key_name, Action, PixelScale},
+    },
+};
+
+const VOLUME_STEP: f32 = 0.1;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum SettingsPage {
+    Main,
+    Video,
+    Audio,
+    Controls,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum SettingsItem {
+    Page(SettingsPage),
+    Language,
+    Resolution,
+    Fullscreen,
+    Borderless,
+    VSync,
+    PixelScale,
+    MasterVolume,
+    Volume(AudioCategory),
+    Binding(Action),
+    Back,
+}
+
+fn page_items(page: SettingsPage) -> Vec<SettingsItem> {
+    let mut items = match page {
+        SettingsPage::Main => vec![
+            SettingsItem::Page(SettingsPage::Video),
+            SettingsItem::Page(SettingsPage::Audio),
+            SettingsItem::Page(SettingsPage::Controls),
+            SettingsItem::Language,
+        ],
+        SettingsPage::Video => vec![
+            SettingsItem::Resolution,
+            SettingsItem::Fullscreen,
+            SettingsItem::Borderless,
+            SettingsItem::VSync,
+            SettingsItem::PixelScale,
+        ],
+        SettingsPage::Audio => vec![
+            SettingsItem::MasterVolume,
+            SettingsItem::Volume(AudioCategory::Music),
+            SettingsItem::Volume(AudioCategory::Ambience),
+            SettingsItem::Volume(AudioCategory::Sfx),
+            SettingsItem::Volume(AudioCategory::Voice),
+            SettingsItem::Volume(AudioCategory::Ui),
+        ],
+        SettingsPage::Controls => Action::iter().map(SettingsItem::Binding).collect(),
+    };
+    items.push(SettingsItem::Back);
+    items
+}
+
+/// Video, audio, controls and language options, applied as soon as they change and
+/// saved when the menu is left.
+pub struct SettingsScene {
+    menu: MenuUi,
+    page: SettingsPage,
+    items: Vec<SettingsItem>,
+    /// The action waiting for a new key.
+    rebinding: Option<Action>,
+}
+
+impl SettingsScene {
+    pub fn new(shared: &mut SharedState) -> Self {
+        SettingsScene {
+            menu: MenuUi::new(&shared.ui_images),
+            page: SettingsPage::Main,
+            items: page_items(SettingsPage::Main),
+            rebinding: None,
+        }
+    }
+
+    fn open_page(&mut self, shared: &SharedState, page: SettingsPage) {
+        // A fresh menu so the selection starts at the top
+        self.menu = MenuUi::new(&shared.ui_images);
+        self.page = page;
+        self.items = page_items(page);
+    }
+
+    fn item_text(&self, shared: &SharedState, item: SettingsItem) -> String {
+        let tr = |key| shared.localization.tr(key);
+        let on_off = |on: bool| tr(if on { "settings.on" } else { "settings.off" }).to_string();
+        let video = &shared.settings.video;
+        let volumes = shared.audio_system.volumes();
+        let percent = |volume: f32| format!("{}%", (volume * 100.0).round());
+
+        let (label, value) = match item {
+            SettingsItem::Page(page) => return tr(page_title(page)).to_string(),
+            SettingsItem::Language => (tr("settings.language"), tr("language.name").to_string()),
+            SettingsItem::Resolution => (
+                tr("settings.resolution"),
+                format!("{}x{}", video.width, video.height),
+            ),
+            SettingsItem::Fullscreen => (tr("settings.fullscreen"), on_off(shared.viewport.fullscreen)),
+            SettingsItem::Borderless => (tr("settings.borderless"), on_off(video.borderless)),
+            SettingsItem::VSync => (tr("settings.vsync"), on_off(video.vsync)),
+            SettingsItem::PixelScale => {
+                let value = match video.pixel_scale {
+                    PixelScale::Integer => tr("settings.integer"),
+                    PixelScale::Fractional => tr("settings.fractional"),
+                };
+                (tr("settings.pixel_scale"), value.to_string())
+            }
+            SettingsItem::MasterVolume => (tr("settings.master_volume"), percent(volumes.master)),
+            SettingsItem::Volume(category) => {
+                let label = match category {
+                    AudioCategory::Music => tr("settings.music_volume"),
+                    AudioCategory::Ambience => tr("settings.ambience_volume"),
+                    AudioCategory::Sfx => tr("settings.sfx_volume"),
+                    AudioCategory::Voice => tr("settings.voice_volume"),
+                    AudioCategory::Ui => tr("settings.ui_volume"),
+                };
+                (label, percent(volumes.get(category)))
+            }
+            SettingsItem::Binding(action) if self.rebinding == Some(action) => {
+                (tr(action.text_key()), tr("settings.press_key").to_string())
+            }
+            SettingsItem::Binding(action) => {
+                let keys = shared.settings.controls.keys(action);
+                let names: Vec<String> = keys.into_iter().map(key_label).collect();
+                (tr(action.text_key()), names.join(", "))
+            }
+            SettingsItem::Back => return tr("menu.back").to_string(),
+        };
+        format!("{}: {}", label, value)
+    }
+
+    /// Changes a setting by one step, returns false for entries that can't be adjusted.
+    fn adjust(ctx: &mut Context, shared: &mut SharedState, item: SettingsItem, direction: i32) -> bool {
+        let step = direction as f32 * VOLUME_STEP;
+        let video = &mut shared.settings.video;
+        match item {
+            SettingsItem::Language => shared.change_locale(ctx, direction),
+            SettingsItem::Resolution => {
+                let current = RESOLUTIONS
+                    .iter()
+                    .position(|size| *size == (video.width, video.height))
+                    .unwrap_or(0);
+                let next = (current as i32 + direction).rem_euclid(RESOLUTIONS.len() as i32);
+                let (width, height) = RESOLUTIONS[next as usize];
+                video.width = width;
+                video.height = height;
+                shared.apply_video_settings(ctx);
+            }
+            SettingsItem::Fullscreen => shared.toggle_fullscreen(ctx),
+            SettingsItem::Borderless => {
+                video.borderless = !video.borderless;
+                shared.apply_video_settings(ctx);
+            }
+            // Takes effect on the next start
+            SettingsItem::VSync => video.vsync = !video.vsync,
+            SettingsItem::PixelScale => {
+                video.pixel_scale = match video.pixel_scale {
+                    PixelScale::Integer => PixelScale::Fractional,
+                    PixelScale::Fractional => PixelScale::Integer,
+                };
+                shared.apply_video_settings(ctx);
+            }
+            SettingsItem::MasterVolume => {
+                let mut volumes = shared.audio_system.volumes();
+                volumes.master = (volumes.master + step).clamp(0.0, 1.0);
+                shared.audio_system.set_volumes(volumes);
+            }
+            SettingsItem::Volume(category) => {
+                let current = shared.audio_system.volumes().get(category);
+                shared.audio_system.set_volume(category, current + step);
+            }
+            SettingsItem::Page(_) | SettingsItem::Binding(_) | SettingsItem::Back => return false,
+        }
+        true
+    }
+
+    /// Leaves a sub page for the main one, or the menu itself from the main page.
+    fn back(&mut self, shared: &mut SharedState) -> SceneTransition {
+        if self.page == SettingsPage::Main {
+            shared.save_settings();
+            SceneTransition::Pop
+        } else {
+            self.open_page(shared, SettingsPage::Main);
+            SceneTransition::None
+        }
+    }
+
+    fn rebind_key_down(&mut self, ctx: &mut Context, shared: &mut SharedState, key: KeyCode) {
+        let action = match self.rebinding {
+            Some(action) => action,
+            None => return,
+        };
+        if key == KeyCode::Escape {
+            self.rebinding = None;
+            shared.play_ui_sound(ctx, Some(InteractionSound::Back));
+        } else if key_from_name(&key_name(key)).is_some() {
+            let bound = shared.settings.controls.bind(action, key);
+            self.rebinding = None;
+            let sound = if bound { InteractionSound::Select } else { InteractionSound::Back };
+            shared.play_ui_sound(ctx, Some(sound));
+        }
+    }
+}
+
+fn page_title(page: SettingsPage) -> &'static str {
+    match page {
+        SettingsPage::Main => "settings.title",
+        SettingsPage::Video => "settings.video",
+        SettingsPage::Audio => "settings.audio",
+        SettingsPage::Controls => "settings.controls",
+    }
+}
+
+impl Scene for SettingsScene {
+    fn update(&mut self, _ctx: &mut Context, shared: &mut SharedState) -> GameResult<SceneTransition> {
+        self.menu.set_title(shared.localization.tr(page_title(self.page)));
+        let items = self
+            .items
+            .iter()
+            .map(|item| self.item_text(shared, *item))
+            .collect();
+        self.menu.set_items(items);
+        Ok(SceneTransition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, shared: &mut SharedState) -> GameResult {
+        let size = shared.viewport.virtual_size;
+        let dim = graphics::Mesh::new_rectangle(
+            ctx,
+            DrawMode::fill(),
+            Rect::new(0.0, 0.0, size.width, size.height),
+            Color::new(0.0, 0.0, 0.0, 0.5),
+        )?;
+        graphics::draw(ctx, &dim, DrawParam::default())?;
+        self.menu.draw(ctx, &shared.asset_manager, &size)
+    }
+
+    fn key_down(
+        &mut self,
+        ctx: &mut Context,
+        shared: &mut SharedState,
+        key: KeyCode,
+    ) -> GameResult<SceneTransition> {
+        if self.rebinding.is_some() {
+            self.rebind_key_down(ctx, shared, key);
+            return Ok(SceneTransition::None);
+        }
+
+        let input = self.menu.key_down(key);
+        shared.play_ui_sound(ctx, input.sound());
+
+        Ok(match input {
+            MenuInput::Back => self.back(shared),
+            MenuInput::Chosen(index) => match self.items[index] {
+                SettingsItem::Back => self.back(shared),
+                SettingsItem::Page(page) => {
+                    self.open_page(shared, page);
+                    SceneTransition::None
+                }
+                SettingsItem::Binding(action) => {
+                    self.rebinding = Some(action);
+                    SceneTransition::None
+                }
+                item => {
+                    SettingsScene::adjust(ctx, shared, item, 1);
+                    SceneTransition::None
+                }
+            },
+            MenuInput::Adjusted(index, direction) => {
+                if SettingsScene::adjust(ctx, shared, self.items[index], direction) {
+                    shared.play_ui_sound(ctx, Some(InteractionSound::Move));
+                }
+                SceneTransition::None
+            }
+            _ => SceneTransition::None,
+        })
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+
+    /// The key being bound has to arrive as pressed.
+    fn wants_raw_keys(&self) -> bool {
+        self.rebinding.is_some()
+    }
+}
@@ -0,0 +1,153 @@
+use ggez::{graphics, Context, GameResult};
+use std::path::PathBuf;
+
+use crate::ecs::{
+    assets::{
+        asset_manager::{AssetManager, ImageHandle},
+        resources::ResourceSource,
+    },
+    systems::{
+        audio_system::{audio::AudioCategory, audio_system::AudioSystem},
+        input_system::input_system::InteractionSound,
+        physics_system::physics::Size,
+        render_system::viewport::Viewport,
+    },
+    utils::{
+        cli::CliOptions,
+        constants::{CONSOLE_SCRIPT, LOCALES, UI_FONT_CONFIG, VIRTUAL_H, VIRTUAL_W},
+        localization::Localization,
+        settings::{config_dir, PixelScale, Settings},
+    },
+};
+
+/// Images used by the UI, loaded once through the asset manager.
+#[derive(Copy, Clone)]
+pub struct UiImages {
+    pub dialog_box: ImageHandle,
+    pub avatar_box: ImageHandle,
+}
+
+/// Everything that outlives a single scene: assets, audio, strings, the screen and the
+/// player's settings.
+pub struct SharedState {
+    pub settings: Settings,
+    /// Draw the debug overlay over the world, `--debug` turns it on from the start.
+    pub debug_overlay: bool,
+    /// Console commands to run when the office loads, see `CONSOLE_SCRIPT`.
+    pub console_script: Option<PathBuf>,
+    /// Area the office opens in, `--area` overrides the areas file's start.
+    pub start_area: Option<String>,
+    pub asset_manager: AssetManager,
+    pub audio_system: AudioSystem,
+    pub localization: Localization,
+    pub viewport: Viewport,
+    pub ui_images: UiImages,
+}
+
+impl SharedState {
+    pub fn new(
+        ctx: &mut Context,
+        settings: Settings,
+        resources: ResourceSource,
+        options: &CliOptions,
+        audio_enabled: bool,
+    ) -> GameResult<Self> {
+        let mut asset_manager = AssetManager::new(resources);
+
+        if asset_manager.exists(UI_FONT_CONFIG) {
+            let ui_font = asset_manager.load_font(ctx, UI_FONT_CONFIG)?;
+            asset_manager.set_ui_font(ui_font);
+        }
+
+        let mut localization = Localization::load(&mut asset_manager, LOCALES)?;
+        if !localization.set_locale(&settings.language) {
+            eprintln!("Unknown language {}, using {}", settings.language, localization.locale());
+        }
+        graphics::set_window_title(ctx, localization.tr("window.title"));
+
+        let ui_images = UiImages {
+            dialog_box: asset_manager.load_image(ctx, "/dialog_box.png")?,
+            avatar_box: asset_manager.load_image(ctx, "/avatar_box.png")?,
+        };
+
+        let mut audio_system = AudioSystem::new(&mut asset_manager, audio_enabled);
+        audio_system.set_volumes(settings.audio);
+        audio_system.start_ambience(ctx);
+
+        let mut viewport = Viewport::new(
+            ctx,
+            Size {
+                width: VIRTUAL_W,
+                height: VIRTUAL_H,
+            },
+        )?;
+        viewport.fullscreen = settings.video.fullscreen;
+        viewport.integer_scaling = settings.video.pixel_scale == PixelScale::Integer;
+        let (width, height) = graphics::drawable_size(ctx);
+        viewport.resize(ctx, width, height)?;
+
+        Ok(SharedState {
+            settings,
+            debug_overlay: options.debug_overlay,
+            console_script: options.console_script.clone().or_else(|| {
+                config_dir()
+                    .map(|dir| dir.join(CONSOLE_SCRIPT))
+                    .filter(|path| path.exists())
+            }),
+            start_area: options.start_area.clone(),
+            asset_manager,
+            audio_system,
+            localization,
+            viewport,
+            ui_images,
+        })
+    }
+
+    /// Switches to the next loaded language, or the previous one for a negative `step`,
+    /// retitling the window.
+    pub fn change_locale(&mut self, ctx: &mut Context, step: i32) {
+        if step < 0 {
+            self.localization.previous_locale();
+        } else {
+            self.localization.next_locale();
+        }
+        self.settings.language = self.localization.locale().to_string();
+        graphics::set_window_title(ctx, self.localization.tr("window.title"));
+    }
+
+    pub fn play_ui_sound(&mut self, ctx: &mut Context, sound: Option<InteractionSound>) {
+        if let Some(sound) = sound {
+            self.audio_system.play(ctx, sound.name(), AudioCategory::Ui);
+        }
+    }
+
+    pub fn toggle_fullscreen(&mut self, ctx: &mut Context) {
+        if let Err(error) = self.viewport.toggle_fullscreen(ctx) {
+            eprintln!("Couldn't toggle fullscreen: {}", error);
+        }
+        self.settings.video.fullscreen = self.viewport.fullscreen;
+    }
+
+    /// Applies `settings.video` to the window and the viewport. Vsync can only be set
+    /// when the window is created, so it waits for the next start.
+    pub fn apply_video_settings(&mut self, ctx: &mut Context) {
+        self.viewport.fullscreen = self.settings.video.fullscreen;
+        self.viewport.integer_scaling = self.settings.video.pixel_scale == PixelScale::Integer;
+
+        let result = graphics::set_mode(ctx, self.settings.window_mode()).and_then(|_| {
+            let (width, height) = graphics::drawable_size(ctx);
+            self.viewport.resize(ctx, width, height)
+        });
+        if let Err(error) = result {
+            eprintln!("Couldn't apply the video settings: {}", error);
+        }
+    }
+
+    /// Writes the current settings to disk, reporting failures.
+    pub fn save_settings(&mut self) {
+        self.settings.audio = self.audio_system.volumes();
+        if let Err(error) = self.settings.save() {
+            eprintln!("Couldn't save the settings: {}", error);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use ggez::{event::KeyCode, graphics, Context, GameResult};
+
+use super::{
+    scene::{Scene, SceneTransition},
+    settings_scene::SettingsScene,
+    shared_state::SharedState,
+};
+use crate::ecs::{
+    game_state::GameState,
+    ui::menu_ui::{MenuInput, MenuUi},
+};
+
+const TITLE_ITEMS: [&str; 3] = ["menu.start", "menu.settings", "menu.quit"];
+
+/// First screen of the game.
+pub struct TitleScene {
+    menu: MenuUi,
+}
+
+impl TitleScene {
+    pub fn new(shared: &mut SharedState) -> Self {
+        TitleScene {
+            menu: MenuUi::new(&shared.ui_images),
+        }
+    }
+}
+
+impl Scene for TitleScene {
+    fn update(&mut self, ctx: &mut Context, shared: &mut SharedState) -> GameResult<SceneTransition> {
+        let localization = &shared.localization;
+        self.menu.set_title(localization.tr("window.title"));
+        self.menu
+            .set_items(TITLE_ITEMS.iter().map(|key| localization.tr(key).to_string()).collect());
+        shared.audio_system.play_music(ctx, "dialogue");
+        Ok(SceneTransition::None)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, shared: &mut SharedState) -> GameResult {
+        graphics::clear(ctx, graphics::Color::from_rgb(44, 62, 80));
+        self.menu
+            .draw(ctx, &shared.asset_manager, &shared.viewport.virtual_size)
+    }
+
+    fn key_down(
+        &mut self,
+        ctx: &mut Context,
+        shared: &mut SharedState,
+        key: KeyCode,
+    ) -> GameResult<SceneTransition> {
+        let input = self.menu.key_down(key);
+        shared.play_ui_sound(ctx, input.sound());
+
+        Ok(match input {
+            MenuInput::Chosen(0) => SceneTransition::Replace(Box::new(GameState::new(ctx, shared)?)),
+            MenuInput::Chosen(1) => SceneTransition::Push(Box::new(SettingsScene::new(shared))),
+            MenuInput::Chosen(2) => SceneTransition::Quit,
+            _ => SceneTransition::None,
+        })
+    }
+}
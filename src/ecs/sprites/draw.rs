@@ -1,12 +1,14 @@
-use super::super::systems::{physics_system::physics::*, render_system::camera::Camera};
-use ggez::graphics::spritebatch::SpriteBatch;
+use super::super::systems::{
+    physics_system::physics::*,
+    render_system::{camera::Camera, render_queue::RenderQueue},
+};
 
 pub trait Draw {
-    fn draw(&mut self, batch: &mut SpriteBatch, camera: &Camera, physics: &Physics, frames: usize);
+    fn draw(&mut self, queue: &mut RenderQueue, camera: &Camera, physics: &Physics, frames: usize);
 }
 
 pub trait DrawComponent {
     type Component;
 
-    fn draw_component(&mut self, batch: &mut SpriteBatch, camera: &Camera, physics: &Physics, frames: usize, component: &Self::Component);
-}
\ No newline at end of file
+    fn draw_component(&mut self, queue: &mut RenderQueue, camera: &Camera, physics: &Physics, frames: usize, component: &Self::Component);
+}
@@ -1,50 +1,65 @@
 use ggez::{
-    graphics::spritebatch::SpriteBatch,
     mint::{Point2, Vector2},
+    GameResult,
 };
-use super::super::atlas;
+use std::collections::HashMap;
+
+use super::super::{assets::asset_manager::ImageHandle, atlas};
 use super::{
     super::systems::{
-        physics_system::physics::*, render_system::camera::Camera,
+        physics_system::physics::*,
+        render_system::{
+            camera::Camera,
+            render_queue::{RenderLayer, RenderQueue},
+        },
     },
     sprite::Sprite,
 };
 use super::super::components::npc::Npc;
-use super::super::utils::constants::NPC_COUNT;
 
+/// Every frame of the NPC atlas by name, each NPC picks its own.
 pub struct NpcSprite {
-    pub sprites: Vec<Sprite>,
+    pub sprites: HashMap<String, Sprite>,
+    pub image: ImageHandle,
 }
 
 impl NpcSprite {
-    pub fn new(atlas: &atlas::Atlas) -> Self {
-        let mut sprites = Vec::new();
-
-        for npc_count in 0..NPC_COUNT {
-            sprites.push(atlas.create_sprite(&format!("npc_{}", npc_count).to_string()));
-        }
+    pub fn new(atlas: &atlas::Atlas, image: ImageHandle) -> GameResult<Self> {
+        let sprites = atlas
+            .frame_names()
+            .map(|name| Ok((name.to_string(), atlas.create_sprite(name)?)))
+            .collect::<GameResult<_>>()?;
 
-        Self {
+        Ok(Self {
             sprites,
-        }
+            image,
+        })
     }
 
     pub fn draw(
         &mut self,
-        batch: &mut SpriteBatch,
+        queue: &mut RenderQueue,
         camera: &Camera,
         physics: &Physics,
         npc: &Npc,
     ) {
-        let s: &mut Sprite = &mut self.sprites[npc.id as usize];
+        let s = match self.sprites.get(&npc.sprite) {
+            Some(sprite) => sprite,
+            None => return,
+        };
         let position = camera.world_to_screen(&physics.position);
 
-        batch.add(s.draw_params(
-            Point2 {
-                x: position.x - s.width,
-                y: position.y - s.height,
-            },
-            Vector2 { x: 2.0, y: 2.0 },
-        ));
+        queue.submit(
+            RenderLayer::Entities,
+            physics.foot_y(),
+            self.image,
+            s.draw_params(
+                Point2 {
+                    x: position.x - s.width * camera.zoom,
+                    y: position.y - s.height * camera.zoom,
+                },
+                Vector2 { x: 2.0 * camera.zoom, y: 2.0 * camera.zoom },
+            ),
+        );
     }
 }
@@ -1,13 +1,19 @@
 use ggez::{
-    graphics::spritebatch::SpriteBatch,
     mint::{Point2, Vector2},
+    GameResult,
 };
 use std::collections::HashMap;
 use strum::IntoEnumIterator;
 
-use super::{super::atlas, draw::Draw};
+use super::{super::assets::asset_manager::ImageHandle, super::atlas, draw::Draw};
 use super::{
-    super::systems::{physics_system::physics::*, render_system::camera::Camera},
+    super::systems::{
+        physics_system::physics::*,
+        render_system::{
+            camera::Camera,
+            render_queue::{RenderLayer, RenderQueue},
+        },
+    },
     sprite::Sprite,
 };
 
@@ -15,10 +21,11 @@ pub struct PlayerSprite {
     pub idle_sprites: HashMap<Direction, Sprite>,
     pub walking_sprites: HashMap<Direction, Vec<Sprite>>,
     pub position: Point2<f32>,
+    pub image: ImageHandle,
 }
 
 impl PlayerSprite {
-    pub fn new(atlas: &atlas::Atlas) -> Self {
+    pub fn new(atlas: &atlas::Atlas, image: ImageHandle) -> GameResult<Self> {
         let mut idle_sprites = HashMap::new();
         let mut walking_sprites = HashMap::new();
 
@@ -27,7 +34,7 @@ impl PlayerSprite {
                 direction,
                 atlas.create_sprite(
                     &format!("player-idle-{}", Direction::to_index(direction)).to_string(),
-                ),
+                )?,
             );
         }
 
@@ -38,24 +45,25 @@ impl PlayerSprite {
             for animation_frame in 0..=6 {
                 direction_sprites.push(atlas.create_sprite(
                     &format!("player-{}-{}", direction_str, animation_frame).to_string(),
-                ));
+                )?);
             }
 
             walking_sprites.insert(direction, direction_sprites);
         }
 
-        Self {
+        Ok(Self {
             idle_sprites,
             position: Point2 { x: 500.0, y: 500.0 },
             walking_sprites,
-        }
+            image,
+        })
     }
 }
 
 impl Draw for PlayerSprite {
     fn draw(
         &mut self,
-        batch: &mut SpriteBatch,
+        queue: &mut RenderQueue,
         camera: &Camera,
         player_physics: &Physics,
         frames: usize,
@@ -71,17 +79,23 @@ impl Draw for PlayerSprite {
 
         let position = camera.world_to_screen(&player_physics.position);
 
-        batch.add(s.draw_params(
-            Point2 {
-                x: position.x - s.width,
-                y: position.y - s.height,
-            },
-            Vector2 { x: 2.0, y: 2.0 },
-        ));
+        queue.submit(
+            RenderLayer::Entities,
+            player_physics.foot_y(),
+            self.image,
+            s.draw_params(
+                Point2 {
+                    x: position.x - s.width * camera.zoom,
+                    y: position.y - s.height * camera.zoom,
+                },
+                Vector2 { x: 2.0 * camera.zoom, y: 2.0 * camera.zoom },
+            ),
+        );
     }
 }
 
-fn animation_sprite_index(frames: usize) -> usize {
+/// Walking animation frame shown `frames` frames into the game.
+pub fn animation_sprite_index(frames: usize) -> usize {
     match frames {
         s if s % 70 < 10 => 0,
         s if s % 70 < 20 => 1,
@@ -0,0 +1,68 @@
+use ggez::{
+    mint::{Point2, Vector2},
+    GameResult,
+};
+use std::collections::HashMap;
+
+use crate::ecs::components::prop::Prop;
+
+use super::{super::assets::asset_manager::ImageHandle, super::atlas, draw::DrawComponent};
+use super::{
+    super::systems::{
+        physics_system::physics::*,
+        render_system::{
+            camera::Camera,
+            render_queue::{RenderLayer, RenderQueue},
+        },
+    },
+    sprite::Sprite,
+};
+
+/// Every frame of the office atlas, by name, for props.
+pub struct PropSprite {
+    pub sprites: HashMap<String, Sprite>,
+    pub image: ImageHandle,
+}
+
+impl PropSprite {
+    pub fn new(atlas: &atlas::Atlas, image: ImageHandle) -> GameResult<Self> {
+        let sprites = atlas
+            .frame_names()
+            .map(|name| Ok((name.to_string(), atlas.create_sprite(name)?)))
+            .collect::<GameResult<_>>()?;
+
+        Ok(Self { sprites, image })
+    }
+}
+
+impl DrawComponent for PropSprite {
+    type Component = Prop;
+
+    fn draw_component(
+        &mut self,
+        queue: &mut RenderQueue,
+        camera: &Camera,
+        physics: &Physics,
+        _frames: usize,
+        component: &Prop,
+    ) {
+        let sprite = match self.sprites.get(&component.frame) {
+            Some(sprite) => sprite,
+            None => return,
+        };
+        let position = camera.world_to_screen(&physics.position);
+
+        queue.submit(
+            RenderLayer::Entities,
+            physics.foot_y(),
+            self.image,
+            sprite.draw_params(
+                Point2 {
+                    x: position.x - sprite.width * camera.zoom,
+                    y: position.y - sprite.height * camera.zoom,
+                },
+                Vector2 { x: 2.0 * camera.zoom, y: 2.0 * camera.zoom },
+            ),
+        );
+    }
+}
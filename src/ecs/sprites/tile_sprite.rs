@@ -1,12 +1,17 @@
 use ggez::{
-    graphics::spritebatch::SpriteBatch,
     mint::{Point2, Vector2},
+    GameResult,
 };
 
-use super::super::atlas;
+use super::super::{assets::asset_manager::ImageHandle, atlas};
 use super::{
-    super::systems::{physics_system::physics::Position, render_system::camera::Camera},
-    super::utils::constants::{INTIAL_WORLD_H, INTIAL_WORLD_W},
+    super::systems::{
+        physics_system::physics::{Position, Size},
+        render_system::{
+            camera::Camera,
+            render_queue::{RenderLayer, RenderQueue},
+        },
+    },
     sprite::Sprite,
 };
 pub const NUMBER_OF_TILES: u8 = 3;
@@ -16,20 +21,25 @@ pub struct TileSprite {
 }
 
 impl TileSprite {
-    pub fn draw(&mut self, batch: &mut SpriteBatch, camera: &Camera) {
+    pub fn draw(&mut self, queue: &mut RenderQueue, camera: &Camera, image: ImageHandle) {
         let s = &mut self.sprite;
         let position = camera.world_to_screen(&Position {
             x: self.position.x,
             y: self.position.y,
         });
 
-        batch.add(s.draw_params(
-            Point2 {
-                x: position.x,
-                y: position.y,
-            },
-            Vector2 { x: 1.0, y: 1.0 },
-        ));
+        queue.submit(
+            RenderLayer::Floor,
+            self.position.y,
+            image,
+            s.draw_params(
+                Point2 {
+                    x: position.x,
+                    y: position.y,
+                },
+                Vector2 { x: camera.zoom, y: camera.zoom },
+            ),
+        );
     }
 
     pub fn new(sprite: Sprite, position: (f32, f32)) -> Self {
@@ -49,15 +59,16 @@ fn create_tile(sprite: Sprite, x: f32, y: f32) -> Box<TileSprite> {
     Box::new(tile)
 }
 
-pub fn create_tiles(sprites: &atlas::Atlas) -> Vec<Box<TileSprite>> {
-    let floor_tile = sprites.create_sprite("floor_tile.png");
+/// Floor tiles covering a `world_size` area.
+pub fn create_tiles(sprites: &atlas::Atlas, world_size: &Size) -> GameResult<Vec<Box<TileSprite>>> {
+    let floor_tile = sprites.create_sprite("floor_tile.png")?;
     let width = floor_tile.width;
     let height = floor_tile.height;
 
     let mut tiles: Vec<Box<TileSprite>> = Vec::new();
 
-    for y in (0..INTIAL_WORLD_H as i32).step_by(height as usize) {
-        let mut tile_row: Vec<Box<TileSprite>> = (0..INTIAL_WORLD_W as i32)
+    for y in (0..world_size.height as i32).step_by(height as usize) {
+        let mut tile_row: Vec<Box<TileSprite>> = (0..world_size.width as i32)
             .step_by(width as usize)
             .into_iter()
             .map(|i| create_tile(floor_tile.clone(), i as f32, y as f32))
@@ -65,5 +76,5 @@ pub fn create_tiles(sprites: &atlas::Atlas) -> Vec<Box<TileSprite>> {
 
         tiles.append(&mut tile_row);
     }
-    tiles
+    Ok(tiles)
 }
@@ -0,0 +1,55 @@
+use crate::ecs::{systems::physics_system::physics::Position, utils::constants::SCENE_FADE_DURATION};
+
+/// Going through a door: the screen darkens, the next area is entered once it's black and
+/// then it clears up again.
+pub enum AreaFade {
+    Out {
+        to: String,
+        /// The area's own spawn point if none.
+        spawn: Option<Position>,
+        elapsed: f32,
+    },
+    In { elapsed: f32 },
+}
+
+impl AreaFade {
+    pub fn new(to: &str, spawn: Option<Position>) -> Self {
+        AreaFade::Out {
+            to: to.to_string(),
+            spawn,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Advances the fade, returning the area to enter and where once the screen is black.
+    pub fn update(&mut self, dt: f32) -> Option<(String, Option<Position>)> {
+        match self {
+            AreaFade::Out { elapsed, .. } if *elapsed + dt < SCENE_FADE_DURATION => {
+                *elapsed += dt;
+                None
+            }
+            AreaFade::Out { to, spawn, .. } => {
+                let entered = (to.clone(), *spawn);
+                *self = AreaFade::In { elapsed: 0.0 };
+                Some(entered)
+            }
+            AreaFade::In { elapsed } => {
+                *elapsed += dt;
+                None
+            }
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        matches!(self, AreaFade::In { elapsed } if *elapsed >= SCENE_FADE_DURATION)
+    }
+
+    /// Opacity of the black layer drawn over the area.
+    pub fn alpha(&self) -> f32 {
+        let alpha = match self {
+            AreaFade::Out { elapsed, .. } => elapsed / SCENE_FADE_DURATION,
+            AreaFade::In { elapsed } => 1.0 - elapsed / SCENE_FADE_DURATION,
+        };
+        alpha.clamp(0.0, 1.0)
+    }
+}
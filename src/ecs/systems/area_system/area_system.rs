@@ -0,0 +1,116 @@
+use ggez::{
+    event::KeyCode,
+    graphics::{self, Color, DrawMode, DrawParam, MeshBuilder, Rect},
+    Context, GameResult,
+};
+
+use super::area::AreaFade;
+use crate::ecs::{
+    components::door::Door,
+    scenes::shared_state::SharedState,
+    systems::{
+        console_system::console::Console,
+        physics_system::{
+            physics::{Direction, Physics},
+            physics_system::objects_collide,
+        },
+        render_system::camera::Camera,
+    },
+    utils::constants::DOOR_REACH,
+};
+
+const DOOR_COLOR: Color = Color::new(0.35, 0.22, 0.12, 1.0);
+const DOOR_FRAME_COLOR: Color = Color::new(0.9, 0.8, 0.55, 1.0);
+
+pub fn register_console_commands(console: &mut Console) {
+    console.register("area", "[name]", "prints the current area, or goes to another one", |_, _, game, args| {
+        if let Some(name) = args.first() {
+            game.travel(name, None)?;
+            return Ok(format!("Going to {}", name));
+        }
+        Ok(format!("In {}, areas: {}", game.area(), game.area_names().join(", ")))
+    });
+}
+
+/// The door the player is walking into, if any.
+pub fn door_in_reach<'a>(
+    player: &Physics,
+    player_mov_actions: &[KeyCode],
+    physics_components: &[Option<Physics>],
+    door_components: &'a [Option<Door>],
+) -> Option<&'a Door> {
+    let directions: Vec<Direction> = player_mov_actions
+        .iter()
+        .filter_map(|key| match key {
+            KeyCode::Up => Some(Direction::Up),
+            KeyCode::Down => Some(Direction::Down),
+            KeyCode::Left => Some(Direction::Left),
+            KeyCode::Right => Some(Direction::Right),
+            _ => None,
+        })
+        .collect();
+
+    door_components.iter().enumerate().find_map(|(index, door)| {
+        let (door, physics) = match (door, &physics_components[index]) {
+            (Some(door), Some(physics)) => (door, physics),
+            _ => return None,
+        };
+        let walked_into = directions.iter().any(|direction| {
+            let (dx, dy) = direction.vector();
+            let mut probe = *player;
+            probe.position.x += dx * DOOR_REACH;
+            probe.position.y += dy * DOOR_REACH;
+            objects_collide(&probe, physics)
+        });
+        if walked_into { Some(door) } else { None }
+    })
+}
+
+/// Draws every door with the name of the area it leads to.
+pub fn draw_doors(
+    ctx: &mut Context,
+    shared: &SharedState,
+    camera: &Camera,
+    physics_components: &[Option<Physics>],
+    door_components: &[Option<Door>],
+) -> GameResult {
+    let font = shared.asset_manager.ui_font();
+    for (index, door) in door_components.iter().enumerate() {
+        let (door, physics) = match (door, &physics_components[index]) {
+            (Some(door), Some(physics)) => (door, physics),
+            _ => continue,
+        };
+        let center = camera.world_to_screen(&physics.position);
+        let width = physics.size.width * camera.zoom;
+        let height = physics.size.height * camera.zoom;
+        let rect = Rect::new(center.x - width / 2.0, center.y - height / 2.0, width, height);
+        let mesh = MeshBuilder::new()
+            .rectangle(DrawMode::fill(), rect, DOOR_COLOR)?
+            .rectangle(DrawMode::stroke(2.0), rect, DOOR_FRAME_COLOR)?
+            .build(ctx)?;
+        graphics::draw(ctx, &mesh, DrawParam::default())?;
+
+        let key = format!("area.{}", door.to);
+        let label = shared.localization.tr(&key);
+        let label_width = font.text_width(ctx, label);
+        font.draw_text(
+            ctx,
+            &shared.asset_manager,
+            label,
+            [center.x - label_width / 2.0, center.y - font.line_height(ctx) / 2.0],
+            Color::WHITE,
+        )?;
+    }
+    Ok(())
+}
+
+pub fn draw_area_fade(ctx: &mut Context, shared: &SharedState, fade: &AreaFade) -> GameResult {
+    let size = shared.viewport.virtual_size;
+    let rect = graphics::Mesh::new_rectangle(
+        ctx,
+        DrawMode::fill(),
+        Rect::new(0.0, 0.0, size.width, size.height),
+        Color::new(0.0, 0.0, 0.0, fade.alpha()),
+    )?;
+    graphics::draw(ctx, &rect, DrawParam::default())
+}
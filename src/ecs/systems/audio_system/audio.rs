@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use super::super::{physics_system::physics::Position, render_system::camera::Camera};
+
+/// How `/audio.json` names the game's sounds, every path relative to the resources root.
+#[derive(Deserialize, Debug, Default)]
+pub struct AudioConfig {
+    #[serde(default)]
+    pub music: HashMap<String, String>,
+    #[serde(default)]
+    pub ambience: Option<String>,
+    #[serde(default)]
+    pub sounds: HashMap<String, String>,
+}
+
+/// Every sound belongs to one category, each with its own volume.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AudioCategory {
+    Music,
+    Ambience,
+    Sfx,
+    Ui,
+    Voice,
+}
+
+/// Volumes between 0 and 1, a category plays at its own volume times `master`.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Volumes {
+    pub master: f32,
+    pub music: f32,
+    pub ambience: f32,
+    pub sfx: f32,
+    pub ui: f32,
+    pub voice: f32,
+}
+
+impl Default for Volumes {
+    fn default() -> Self {
+        Volumes {
+            master: 1.0,
+            music: 0.5,
+            ambience: 0.4,
+            sfx: 0.8,
+            ui: 0.7,
+            voice: 0.6,
+        }
+    }
+}
+
+impl Volumes {
+    /// Effective volume of a category, master included.
+    pub fn of(&self, category: AudioCategory) -> f32 {
+        self.get(category) * self.master
+    }
+
+    /// The category's own volume, without master.
+    pub fn get(&self, category: AudioCategory) -> f32 {
+        match category {
+            AudioCategory::Music => self.music,
+            AudioCategory::Ambience => self.ambience,
+            AudioCategory::Sfx => self.sfx,
+            AudioCategory::Ui => self.ui,
+            AudioCategory::Voice => self.voice,
+        }
+    }
+
+    pub fn set(&mut self, category: AudioCategory, volume: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+        match category {
+            AudioCategory::Music => self.music = volume,
+            AudioCategory::Ambience => self.ambience = volume,
+            AudioCategory::Sfx => self.sfx = volume,
+            AudioCategory::Ui => self.ui = volume,
+            AudioCategory::Voice => self.voice = volume,
+        }
+    }
+}
+
+/// How a positional sound fades between its emitter's `min_distance`, where it plays at
+/// full volume, and `max_distance`, where it can't be heard anymore.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Attenuation {
+    Linear,
+    /// Inverse square falloff, rescaled to reach silence at `max_distance`.
+    InverseSquare,
+    /// Eases out of full volume and into silence.
+    Smooth,
+}
+
+impl Attenuation {
+    pub fn gain(&self, distance: f32, min_distance: f32, max_distance: f32) -> f32 {
+        if distance <= min_distance {
+            return 1.0;
+        }
+        if distance >= max_distance {
+            return 0.0;
+        }
+        let t = (distance - min_distance) / (max_distance - min_distance);
+        match self {
+            Attenuation::Linear => 1.0 - t,
+            Attenuation::InverseSquare => {
+                let ratio = min_distance.max(1.0) / distance;
+                let floor = (min_distance.max(1.0) / max_distance).powi(2);
+                ((ratio * ratio - floor) / (1.0 - floor)).max(0.0)
+            }
+            Attenuation::Smooth => 1.0 - t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// Where positional sounds are heard from, the center of the camera.
+#[derive(Copy, Clone, Debug)]
+pub struct Listener {
+    pub position: Position,
+    /// Horizontal distance at which a sound is panned all the way to one side.
+    pub pan_width: f32,
+}
+
+impl Listener {
+    pub fn from_camera(camera: &Camera) -> Self {
+        Listener {
+            position: camera.position,
+            pan_width: camera.visible_size().w_half(),
+        }
+    }
+
+    pub fn distance(&self, position: &Position) -> f32 {
+        let dx = position.x - self.position.x;
+        let dy = position.y - self.position.y;
+        (dx * dx + dy * dy).sqrt()
+    }
+
+    /// Stereo pan of a sound at `position`, -1 is fully left and 1 fully right.
+    pub fn pan(&self, position: &Position) -> f32 {
+        if self.pan_width <= 0.0 {
+            return 0.0;
+        }
+        ((position.x - self.position.x) / self.pan_width).clamp(-1.0, 1.0)
+    }
+}
@@ -0,0 +1,361 @@
+use ggez::{
+    audio::{SoundData, SoundSource, Source, SpatialSource},
+    Context, GameResult,
+};
+use rand::Rng;
+use std::collections::HashMap;
+
+use super::audio::{AudioCategory, AudioConfig, Listener, Volumes};
+use crate::ecs::{
+    assets::asset_manager::AssetManager,
+    components::audio_emitter::{AudioEmitter, EmitterMode},
+    systems::physics_system::physics::Physics,
+    utils::{
+        constants::{AUDIO_CONFIG, DIALOG_BLIP_INTERVAL, FOOTSTEP_FRAMES, MUSIC_CROSSFADE},
+        rng,
+    },
+};
+
+struct MusicTrack {
+    name: String,
+    source: Source,
+    /// Crossfade level between 0 and 1, multiplied by the music volume.
+    level: f32,
+}
+
+/// Plays music, ambience and one-shot sounds through ggez's audio.
+///
+/// Without an audio device ggez can't create sources, so the system is built disabled and
+/// every call is a no-op. Missing or broken sound files are reported once and then skipped.
+pub struct AudioSystem {
+    enabled: bool,
+    volumes: Volumes,
+    music_tracks: HashMap<String, SoundData>,
+    sounds: HashMap<String, SoundData>,
+    ambience: Option<SoundData>,
+    ambience_source: Option<Source>,
+    music: Option<MusicTrack>,
+    fading_out: Vec<MusicTrack>,
+    last_footstep_frame: Option<usize>,
+    since_blip: f32,
+}
+
+impl AudioSystem {
+    pub fn new(asset_manager: &mut AssetManager, enabled: bool) -> Self {
+        let mut audio_system = AudioSystem {
+            enabled,
+            volumes: Volumes::default(),
+            music_tracks: HashMap::new(),
+            sounds: HashMap::new(),
+            ambience: None,
+            ambience_source: None,
+            music: None,
+            fading_out: Vec::new(),
+            last_footstep_frame: None,
+            since_blip: DIALOG_BLIP_INTERVAL,
+        };
+
+        let config: AudioConfig = match asset_manager
+            .load_config(AUDIO_CONFIG)
+            .and_then(|handle| asset_manager.config(handle))
+        {
+            Ok(config) => config,
+            Err(error) => {
+                eprintln!("Couldn't load the audio config, running muted: {}", error);
+                return audio_system;
+            }
+        };
+
+        audio_system.music_tracks = load_sounds(asset_manager, &config.music);
+        audio_system.sounds = load_sounds(asset_manager, &config.sounds);
+        audio_system.ambience = config.ambience.and_then(|path| load_sound(asset_manager, &path));
+        audio_system
+    }
+
+    pub fn volumes(&self) -> Volumes {
+        self.volumes
+    }
+
+    /// Changes a category's volume, looping sounds pick it up right away.
+    pub fn set_volume(&mut self, category: AudioCategory, volume: f32) {
+        self.volumes.set(category, volume);
+        self.apply_volumes();
+    }
+
+    pub fn set_volumes(&mut self, volumes: Volumes) {
+        self.volumes = volumes;
+        self.apply_volumes();
+    }
+
+    /// Starts the looping office ambience, if it isn't playing yet.
+    pub fn start_ambience(&mut self, ctx: &mut Context) {
+        if self.ambience_source.is_some() {
+            return;
+        }
+        let volume = self.volumes.of(AudioCategory::Ambience);
+        if let Some(data) = self.ambience.clone() {
+            self.ambience_source = self.start_loop(ctx, data, volume);
+        }
+    }
+
+    /// Crossfades to the music track `name` over `MUSIC_CROSSFADE` seconds. Asking for the
+    /// track that's already playing does nothing.
+    pub fn play_music(&mut self, ctx: &mut Context, name: &str) {
+        if self.music.as_ref().is_some_and(|track| track.name == name) {
+            return;
+        }
+
+        if let Some(current) = self.music.take() {
+            self.fading_out.push(current);
+        }
+        // A track that's still fading out fades back in from where it was
+        self.music = match self.fading_out.iter().position(|track| track.name == name) {
+            Some(index) => Some(self.fading_out.remove(index)),
+            None => {
+                let data = self.music_tracks.get(name).cloned();
+                data.and_then(|data| self.start_loop(ctx, data, 0.0))
+                    .map(|source| MusicTrack {
+                        name: name.to_string(),
+                        source,
+                        level: 0.0,
+                    })
+            }
+        };
+    }
+
+    /// Plays a one-shot sound at its category's volume.
+    pub fn play(&mut self, ctx: &mut Context, name: &str, category: AudioCategory) {
+        let volume = self.volumes.of(category);
+        self.play_with(ctx, name, volume, 1.0);
+    }
+
+    /// Plays a one-shot sound at an explicit volume and pitch.
+    pub fn play_with(&mut self, ctx: &mut Context, name: &str, volume: f32, pitch: f32) {
+        if !self.enabled || volume <= 0.0 {
+            return;
+        }
+        if let Some(data) = self.sounds.get(name).cloned() {
+            let result = Source::from_data(ctx, data).and_then(|mut source| {
+                source.set_volume(volume);
+                source.set_pitch(pitch);
+                source.play_detached(ctx)
+            });
+            self.report(result);
+        }
+    }
+
+    /// Plays a one-shot sound panned between the speakers, -1 being fully left.
+    pub fn play_at(&mut self, ctx: &mut Context, name: &str, volume: f32, pitch: f32, pan: f32) {
+        if volume <= 0.0 {
+            return;
+        }
+        let result = self
+            .spatial_source(ctx, name, volume, pitch, pan)
+            .map(|mut source| source.play_detached(ctx));
+        if let Some(result) = result {
+            self.report(result);
+        }
+    }
+
+    /// Plays the emitters' sounds from their entity's `Physics.position`, as heard by
+    /// `listener`. Looping emitters are remixed every frame as things move.
+    pub fn update_emitters(
+        &mut self,
+        ctx: &mut Context,
+        listener: &Listener,
+        emitters: &mut [Option<AudioEmitter>],
+        physics_components: &[Option<Physics>],
+    ) {
+        let dt = ggez::timer::delta(ctx).as_secs_f32();
+
+        for (emitter, physics) in emitters.iter_mut().zip(physics_components) {
+            let (emitter, physics) = match (emitter, physics) {
+                (Some(emitter), Some(physics)) => (emitter, physics),
+                _ => continue,
+            };
+
+            let distance = listener.distance(&physics.position);
+            let gain = emitter
+                .attenuation
+                .gain(distance, emitter.min_distance, emitter.max_distance);
+            let volume = gain * emitter.volume * self.volumes.of(emitter.category);
+            let pan = listener.pan(&physics.position);
+
+            match emitter.mode {
+                EmitterMode::Loop => self.update_loop(ctx, emitter, volume, pan),
+                EmitterMode::Every {
+                    min_interval,
+                    max_interval,
+                } => {
+                    emitter.cooldown -= dt;
+                    if emitter.cooldown <= 0.0 {
+                        emitter.cooldown = rng::with_rng(|rng| {
+                            rng.gen_range(min_interval..=max_interval.max(min_interval))
+                        });
+                        self.play_at(ctx, &emitter.sound, volume, emitter.pitch, pan);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Footsteps land on `FOOTSTEP_FRAMES` of the player's walking animation.
+    pub fn footsteps(&mut self, ctx: &mut Context, walking: bool, animation_frame: usize) {
+        if !walking {
+            self.last_footstep_frame = None;
+            return;
+        }
+        if self.last_footstep_frame != Some(animation_frame) {
+            self.last_footstep_frame = Some(animation_frame);
+            if FOOTSTEP_FRAMES.contains(&animation_frame) {
+                self.play(ctx, "footstep", AudioCategory::Sfx);
+            }
+        }
+    }
+
+    /// One blip for characters revealed by the typewriter, at most every
+    /// `DIALOG_BLIP_INTERVAL` seconds, pitched to the speaker's voice.
+    pub fn dialog_blip(&mut self, ctx: &mut Context, revealed: usize, voice_pitch: f32) {
+        if revealed == 0 || self.since_blip < DIALOG_BLIP_INTERVAL {
+            return;
+        }
+        self.since_blip = 0.0;
+        let volume = self.volumes.of(AudioCategory::Voice);
+        self.play_with(ctx, "blip", volume, voice_pitch);
+    }
+
+    pub fn update(&mut self, ctx: &mut Context) {
+        let dt = ggez::timer::delta(ctx).as_secs_f32();
+        self.since_blip += dt;
+
+        let step = if MUSIC_CROSSFADE > 0.0 { dt / MUSIC_CROSSFADE } else { 1.0 };
+        if let Some(track) = self.music.as_mut() {
+            track.level = (track.level + step).min(1.0);
+        }
+        for track in self.fading_out.iter_mut() {
+            track.level = (track.level - step).max(0.0);
+        }
+
+        let (silent, fading): (Vec<MusicTrack>, Vec<MusicTrack>) = self
+            .fading_out
+            .drain(..)
+            .partition(|track| track.level <= 0.0);
+        self.fading_out = fading;
+        for mut track in silent {
+            let result = track.source.stop(ctx);
+            self.report(result);
+        }
+
+        self.apply_volumes();
+    }
+
+    fn apply_volumes(&mut self) {
+        let music_volume = self.volumes.of(AudioCategory::Music);
+        for track in self.music.iter_mut().chain(self.fading_out.iter_mut()) {
+            track.source.set_volume(music_volume * track.level);
+        }
+        if let Some(ambience) = self.ambience_source.as_mut() {
+            ambience.set_volume(self.volumes.of(AudioCategory::Ambience));
+        }
+    }
+
+    /// Out of range loops are stopped so they don't hold on to a sink.
+    fn update_loop(&mut self, ctx: &mut Context, emitter: &mut AudioEmitter, volume: f32, pan: f32) {
+        match emitter.source.as_mut() {
+            Some(source) if volume > 0.0 => {
+                source.set_volume(volume);
+                source.set_position([pan, 0.0, 0.0]);
+            }
+            Some(source) => {
+                let result = source.stop(ctx);
+                emitter.source = None;
+                self.report(result);
+            }
+            None if volume > 0.0 => {
+                let source = self.spatial_source(ctx, &emitter.sound, volume, emitter.pitch, pan);
+                emitter.source = source.and_then(|mut source| {
+                    source.set_repeat(true);
+                    let result = source.play(ctx);
+                    let playing = result.is_ok();
+                    self.report(result);
+                    playing.then_some(source)
+                });
+            }
+            None => (),
+        }
+    }
+
+    /// The ears stay at -1 and 1 on the x axis and the emitter slides between them, so rodio
+    /// only pans the sound while the distance falloff is done by `Attenuation`.
+    fn spatial_source(
+        &mut self,
+        ctx: &mut Context,
+        name: &str,
+        volume: f32,
+        pitch: f32,
+        pan: f32,
+    ) -> Option<SpatialSource> {
+        if !self.enabled {
+            return None;
+        }
+        let data = self.sounds.get(name).cloned()?;
+        let result = SpatialSource::from_data(ctx, data).map(|mut source| {
+            source.set_ears([-1.0, 0.0, 0.0], [1.0, 0.0, 0.0]);
+            source.set_position([pan, 0.0, 0.0]);
+            source.set_volume(volume);
+            source.set_pitch(pitch);
+            source
+        });
+        match result {
+            Ok(source) => Some(source),
+            Err(error) => {
+                self.report(Err(error));
+                None
+            }
+        }
+    }
+
+    fn start_loop(&mut self, ctx: &mut Context, data: SoundData, volume: f32) -> Option<Source> {
+        if !self.enabled {
+            return None;
+        }
+        let result = Source::from_data(ctx, data).and_then(|mut source| {
+            source.set_repeat(true);
+            source.set_volume(volume);
+            source.play(ctx)?;
+            Ok(source)
+        });
+        match result {
+            Ok(source) => Some(source),
+            Err(error) => {
+                self.report(Err(error));
+                None
+            }
+        }
+    }
+
+    /// A device that stops working mutes the game instead of failing every frame.
+    fn report(&mut self, result: GameResult) {
+        if let Err(error) = result {
+            eprintln!("Audio error, muting: {}", error);
+            self.enabled = false;
+        }
+    }
+}
+
+fn load_sounds(asset_manager: &mut AssetManager, paths: &HashMap<String, String>) -> HashMap<String, SoundData> {
+    paths
+        .iter()
+        .filter_map(|(name, path)| Some((name.clone(), load_sound(asset_manager, path)?)))
+        .collect()
+}
+
+fn load_sound(asset_manager: &mut AssetManager, path: &str) -> Option<SoundData> {
+    match asset_manager.load_sound(path) {
+        Ok(handle) => Some(asset_manager.sound(handle)),
+        Err(error) => {
+            eprintln!("Couldn't load sound {}: {}", path, error);
+            None
+        }
+    }
+}
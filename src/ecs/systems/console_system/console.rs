@@ -0,0 +1,163 @@
+use ggez::{event::KeyCode, Context};
+use std::collections::BTreeMap;
+
+use crate::ecs::{game_state::GameState, scenes::shared_state::SharedState, utils::constants::CONSOLE_LOG_LINES};
+
+/// What a command prints back, or why it failed.
+pub type CommandResult = Result<String, String>;
+
+/// A console command, called with the words typed after its name.
+pub type CommandFn = fn(&mut Context, &mut SharedState, &mut GameState, &[&str]) -> CommandResult;
+
+#[derive(Copy, Clone)]
+pub struct ConsoleCommand {
+    /// Arguments, shown by `help`, e.g. `<x> <y>`.
+    pub usage: &'static str,
+    pub description: &'static str,
+    pub run: CommandFn,
+}
+
+/// The developer console's state: registered commands, the line being typed, its history
+/// and the output. Systems add their commands with `register`.
+pub struct Console {
+    commands: BTreeMap<&'static str, ConsoleCommand>,
+    open: bool,
+    input: String,
+    history: Vec<String>,
+    /// Position while browsing the history with Up and Down.
+    history_index: Option<usize>,
+    log: Vec<String>,
+}
+
+impl Console {
+    pub fn new() -> Self {
+        Console {
+            commands: BTreeMap::new(),
+            open: false,
+            input: String::new(),
+            history: Vec::new(),
+            history_index: None,
+            log: Vec::new(),
+        }
+    }
+
+    pub fn register(&mut self, name: &'static str, usage: &'static str, description: &'static str, run: CommandFn) {
+        self.commands.insert(name, ConsoleCommand { usage, description, run });
+    }
+
+    pub fn command(&self, name: &str) -> Option<ConsoleCommand> {
+        self.commands.get(name).copied()
+    }
+
+    pub fn commands(&self) -> impl Iterator<Item = (&'static str, &ConsoleCommand)> {
+        self.commands.iter().map(|(name, command)| (*name, command))
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// The most recent `count` lines of output, oldest first.
+    pub fn last_lines(&self, count: usize) -> &[String] {
+        &self.log[self.log.len().saturating_sub(count)..]
+    }
+
+    pub fn print(&mut self, text: &str) {
+        for line in text.lines() {
+            self.log.push(line.to_string());
+        }
+        if self.log.len() > CONSOLE_LOG_LINES {
+            self.log.drain(..self.log.len() - CONSOLE_LOG_LINES);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.log.clear();
+    }
+
+    pub fn text_input(&mut self, ch: char) {
+        // The key that opens the console also types its character
+        if ch != '`' && !ch.is_control() {
+            self.input.push(ch);
+        }
+    }
+
+    /// Edits the input line, returning it once Return is pressed.
+    pub fn key_down(&mut self, key: KeyCode) -> Option<String> {
+        match key {
+            KeyCode::Return | KeyCode::NumpadEnter => {
+                let line = std::mem::take(&mut self.input);
+                self.history_index = None;
+                if !line.trim().is_empty() && self.history.last() != Some(&line) {
+                    self.history.push(line.clone());
+                }
+                return Some(line);
+            }
+            KeyCode::Back => {
+                self.input.pop();
+            }
+            KeyCode::Escape | KeyCode::Grave => self.open = false,
+            KeyCode::Up if !self.history.is_empty() => {
+                let index = self
+                    .history_index
+                    .map_or(self.history.len() - 1, |index| index.saturating_sub(1));
+                self.history_index = Some(index);
+                self.input = self.history[index].clone();
+            }
+            KeyCode::Down => match self.history_index {
+                Some(index) if index + 1 < self.history.len() => {
+                    self.history_index = Some(index + 1);
+                    self.input = self.history[index + 1].clone();
+                }
+                _ => {
+                    self.history_index = None;
+                    self.input.clear();
+                }
+            },
+            _ => (),
+        }
+        None
+    }
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Console::new()
+    }
+}
+
+/// Splits a command line into words, `"quoted words"` staying together.
+pub fn split_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    for ch in line.chars() {
+        match ch {
+            '"' => quoted = !quoted,
+            ch if ch.is_whitespace() && !quoted => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            ch => word.push(ch),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Parses a command's argument, naming it in the error.
+pub fn parse_arg<T: std::str::FromStr>(args: &[&str], index: usize, name: &str) -> Result<T, String> {
+    let arg = args.get(index).ok_or_else(|| format!("missing {}", name))?;
+    arg.parse().map_err(|_| format!("invalid {}: {}", name, arg))
+}
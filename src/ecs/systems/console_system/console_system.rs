@@ -0,0 +1,102 @@
+use ggez::{
+    graphics::{self, Color, DrawMode, DrawParam, Rect},
+    Context, GameResult,
+};
+use std::fs;
+use std::path::Path;
+
+use super::console::{split_words, Console};
+use crate::ecs::{
+    game_state::GameState,
+    scenes::shared_state::SharedState,
+    utils::constants::{CONSOLE_PADDING, CONSOLE_VISIBLE_LINES},
+};
+
+/// The console's own commands.
+pub fn register_console_commands(console: &mut Console) {
+    console.register("help", "", "lists the commands", |_, _, game, _| {
+        let lines: Vec<String> = game
+            .console
+            .commands()
+            .map(|(name, command)| format!("{} {} - {}", name, command.usage, command.description))
+            .collect();
+        Ok(lines.join("\n"))
+    });
+    console.register("clear", "", "clears the output", |_, _, game, _| {
+        game.console.clear();
+        Ok(String::new())
+    });
+    console.register("exec", "<file>", "runs the commands in a file", |ctx, shared, game, args| {
+        let path = args.first().ok_or("missing file")?;
+        run_script(ctx, shared, game, Path::new(path))?;
+        Ok(String::new())
+    });
+}
+
+/// Runs one line typed in the console or read from a script, printing it and its output.
+pub fn execute(ctx: &mut Context, shared: &mut SharedState, game: &mut GameState, line: &str) {
+    let words = split_words(line);
+    let (name, args) = match words.split_first() {
+        Some((name, args)) => (name, args),
+        None => return,
+    };
+    game.console.print(&format!("> {}", line.trim()));
+
+    let command = match game.console.command(name) {
+        Some(command) => command,
+        None => {
+            game.console.print(&format!("Unknown command {}, try help", name));
+            return;
+        }
+    };
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match (command.run)(ctx, shared, game, &args) {
+        Ok(output) if output.is_empty() => (),
+        Ok(output) => game.console.print(&output),
+        Err(error) => game
+            .console
+            .print(&format!("{}: {} (usage: {} {})", name, error, name, command.usage)),
+    }
+}
+
+/// Runs every line of a script, skipping blank lines and `#` comments.
+pub fn run_script(ctx: &mut Context, shared: &mut SharedState, game: &mut GameState, path: &Path) -> Result<(), String> {
+    let script = fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+    for line in script.lines() {
+        let line = line.trim();
+        if !line.is_empty() && !line.starts_with('#') {
+            execute(ctx, shared, game, line);
+        }
+    }
+    Ok(())
+}
+
+/// Draws the console over the top part of the screen, in virtual coordinates.
+pub fn draw_console(ctx: &mut Context, shared: &SharedState, console: &Console) -> GameResult {
+    let font = shared.asset_manager.ui_font();
+    let line_height = font.line_height(ctx);
+    let width = shared.viewport.virtual_size.width;
+    let height = (CONSOLE_VISIBLE_LINES + 1) as f32 * line_height + 2.0 * CONSOLE_PADDING;
+
+    let background = graphics::Mesh::new_rectangle(
+        ctx,
+        DrawMode::fill(),
+        Rect::new(0.0, 0.0, width, height),
+        Color::new(0.0, 0.0, 0.0, 0.75),
+    )?;
+    graphics::draw(ctx, &background, DrawParam::default())?;
+
+    let mut y = CONSOLE_PADDING;
+    for line in console.last_lines(CONSOLE_VISIBLE_LINES) {
+        font.draw_text(ctx, &shared.asset_manager, line, [CONSOLE_PADDING, y], Color::from_rgb(200, 200, 200))?;
+        y += line_height;
+    }
+    let y = height - CONSOLE_PADDING - line_height;
+    font.draw_text(
+        ctx,
+        &shared.asset_manager,
+        &format!("> {}_", console.input()),
+        [CONSOLE_PADDING, y],
+        Color::WHITE,
+    )
+}
@@ -0,0 +1,200 @@
+use ggez::{
+    graphics::{self, Color, DrawMode, DrawParam, MeshBuilder, Rect},
+    Context, GameResult,
+};
+
+use super::frame_stats::FrameStats;
+use crate::ecs::{
+    game_state::{EntityIndex, GameState},
+    scenes::shared_state::SharedState,
+    systems::{
+        console_system::console::Console,
+        physics_system::physics::Physics,
+        render_system::camera::Camera,
+    },
+    utils::constants::{FRAME_GRAPH_H, FRAME_GRAPH_SAMPLES, INTERACTION_RANGE, TARGET_FRAME_TIME},
+};
+
+const PLAYER_COLOR: Color = Color::new(0.2, 1.0, 0.2, 1.0);
+const NPC_COLOR: Color = Color::new(0.4, 0.6, 1.0, 1.0);
+const DESK_COLOR: Color = Color::new(1.0, 0.6, 0.2, 1.0);
+const OTHER_COLOR: Color = Color::new(0.8, 0.8, 0.8, 1.0);
+const FOCUS_COLOR: Color = Color::new(1.0, 1.0, 0.0, 1.0);
+const RANGE_COLOR: Color = Color::new(1.0, 0.3, 1.0, 1.0);
+const DEAD_ZONE_COLOR: Color = Color::new(1.0, 1.0, 1.0, 0.35);
+const PATH_COLOR: Color = Color::new(0.4, 1.0, 1.0, 1.0);
+const SLOW_FRAME_COLOR: Color = Color::new(1.0, 0.3, 0.3, 1.0);
+
+pub fn register_console_commands(console: &mut Console) {
+    console.register("debug", "", "toggles the debug overlay", |_, shared, _, _| {
+        shared.debug_overlay = !shared.debug_overlay;
+        Ok(format!("Debug overlay {}", if shared.debug_overlay { "on" } else { "off" }))
+    });
+}
+
+/// Draws collision boxes, entity indices and components, where scheduled NPCs are walking
+/// to, the player's interaction range and focus, the camera's dead zone and the frame time
+/// graph, in virtual coordinates.
+pub fn draw_debug_overlay(ctx: &mut Context, shared: &SharedState, game: &GameState) -> GameResult {
+    let camera = &game.camera;
+    let screen = shared.viewport.virtual_size;
+    let mut mesh = MeshBuilder::new();
+
+    // The camera only follows once the player leaves the middle third
+    for third in 1..3 {
+        let x = screen.width * third as f32 / 3.0;
+        let y = screen.height * third as f32 / 3.0;
+        mesh.line(&[[x, 0.0], [x, screen.height]], 1.0, DEAD_ZONE_COLOR)?;
+        mesh.line(&[[0.0, y], [screen.width, y]], 1.0, DEAD_ZONE_COLOR)?;
+    }
+    mesh.rectangle(DrawMode::stroke(2.0), camera.dead_zone(), DEAD_ZONE_COLOR)?;
+
+    for (index, physics) in game.physics_components.iter().enumerate() {
+        if let Some(physics) = physics {
+            mesh.rectangle(DrawMode::stroke(1.0), screen_rect(camera, physics), entity_color(game, index))?;
+        }
+    }
+
+    // Schedules walk NPCs in a straight line to their target
+    for (index, schedule) in game.schedule_components.iter().enumerate() {
+        let (physics, target) = match (&game.physics_components[index], schedule) {
+            (Some(physics), Some(schedule)) => match schedule.target {
+                Some(target) => (physics, target),
+                None => continue,
+            },
+            _ => continue,
+        };
+        let from = camera.world_to_screen(&physics.position);
+        let to = camera.world_to_screen(&target);
+        mesh.line(&[[from.x, from.y], [to.x, to.y]], 1.0, PATH_COLOR)?;
+        mesh.circle(DrawMode::fill(), [to.x, to.y], 3.0, 0.5, PATH_COLOR)?;
+    }
+
+    let player = &game.player_physics;
+    let player_rect = screen_rect(camera, player);
+    mesh.rectangle(DrawMode::stroke(1.0), player_rect, PLAYER_COLOR)?;
+    let range = INTERACTION_RANGE * camera.zoom;
+    let range_rect = Rect::new(
+        player_rect.x - range,
+        player_rect.y - range,
+        player_rect.w + 2.0 * range,
+        player_rect.h + 2.0 * range,
+    );
+    mesh.rectangle(DrawMode::stroke(1.0), range_rect, RANGE_COLOR)?;
+
+    let focused = player
+        .current_focus
+        .and_then(|index| game.physics_components.get(index).copied().flatten());
+    if let Some(focused) = focused {
+        let focus_rect = screen_rect(camera, &focused);
+        mesh.rectangle(DrawMode::stroke(3.0), focus_rect, FOCUS_COLOR)?;
+        mesh.line(&[player_rect.center(), focus_rect.center()], 1.0, FOCUS_COLOR)?;
+    }
+
+    let mesh = mesh.build(ctx)?;
+    graphics::draw(ctx, &mesh, DrawParam::default())?;
+
+    let font = shared.asset_manager.ui_font();
+    let line_height = font.line_height(ctx);
+    for (index, physics) in game.physics_components.iter().enumerate() {
+        if let Some(physics) = physics {
+            let rect = screen_rect(camera, physics);
+            let label = format!("#{} {}", index, component_names(game, index).join(" "));
+            font.draw_text(ctx, &shared.asset_manager, &label, [rect.x, rect.y - line_height], entity_color(game, index))?;
+        }
+    }
+
+    draw_frame_stats(ctx, shared, &game.frame_stats)
+}
+
+/// Frame time graph in the bottom left corner, with the average time of every system.
+fn draw_frame_stats(ctx: &mut Context, shared: &SharedState, stats: &FrameStats) -> GameResult {
+    let screen = shared.viewport.virtual_size;
+    let bar_w = 2.0;
+    let left = 8.0;
+    let bottom = screen.height - 8.0;
+    // The graph tops out at two target frames
+    let scale = FRAME_GRAPH_H / (2.0 * TARGET_FRAME_TIME);
+
+    let mut mesh = MeshBuilder::new();
+    mesh.rectangle(
+        DrawMode::fill(),
+        Rect::new(left, bottom - FRAME_GRAPH_H, FRAME_GRAPH_SAMPLES as f32 * bar_w, FRAME_GRAPH_H),
+        Color::new(0.0, 0.0, 0.0, 0.6),
+    )?;
+    for (sample, dt) in stats.frame_times().enumerate() {
+        let height = (dt * scale).clamp(1.0, FRAME_GRAPH_H);
+        let color = if dt > TARGET_FRAME_TIME * 1.05 { SLOW_FRAME_COLOR } else { PLAYER_COLOR };
+        mesh.rectangle(
+            DrawMode::fill(),
+            Rect::new(left + sample as f32 * bar_w, bottom - height, bar_w, height),
+            color,
+        )?;
+    }
+    let target_y = bottom - TARGET_FRAME_TIME * scale;
+    mesh.line(
+        &[[left, target_y], [left + FRAME_GRAPH_SAMPLES as f32 * bar_w, target_y]],
+        1.0,
+        Color::WHITE,
+    )?;
+    let mesh = mesh.build(ctx)?;
+    graphics::draw(ctx, &mesh, DrawParam::default())?;
+
+    let font = shared.asset_manager.ui_font();
+    let line_height = font.line_height(ctx);
+    let fps = ggez::timer::fps(ctx);
+    let mut lines = vec![format!("{:.0} fps, {:.1} ms", fps, 1000.0 / fps.max(1.0))];
+    lines.extend(
+        stats
+            .timings()
+            .iter()
+            .map(|(name, seconds)| format!("{} {:.2} ms", name, seconds * 1000.0)),
+    );
+
+    let mut y = bottom - FRAME_GRAPH_H - lines.len() as f32 * line_height;
+    for line in lines {
+        font.draw_text(ctx, &shared.asset_manager, &line, [left, y], Color::WHITE)?;
+        y += line_height;
+    }
+    Ok(())
+}
+
+fn screen_rect(camera: &Camera, physics: &Physics) -> Rect {
+    let position = camera.world_to_screen(&physics.position);
+    Rect::new(
+        position.x - physics.size.w_half() * camera.zoom,
+        position.y - physics.size.h_half() * camera.zoom,
+        physics.size.width * camera.zoom,
+        physics.size.height * camera.zoom,
+    )
+}
+
+fn entity_color(game: &GameState, index: EntityIndex) -> Color {
+    if game.npcs_components[index].is_some() {
+        NPC_COLOR
+    } else if game.desk_components[index].is_some() {
+        DESK_COLOR
+    } else {
+        OTHER_COLOR
+    }
+}
+
+fn component_names(game: &GameState, index: EntityIndex) -> Vec<&'static str> {
+    let components = [
+        ("Physics", game.physics_components[index].is_some()),
+        ("Npc", game.npcs_components[index].is_some()),
+        ("Interaction", game.npcs_interactions[index].is_some()),
+        ("Desk", game.desk_components[index].is_some()),
+        ("AudioEmitter", game.emitter_components[index].is_some()),
+        ("Prop", game.prop_components[index].is_some()),
+        ("Schedule", game.schedule_components[index].is_some()),
+        ("Light", game.light_components[index].is_some()),
+        ("Interactable", game.interactable_components[index].is_some()),
+        ("Door", game.door_components[index].is_some()),
+    ];
+    components
+        .iter()
+        .filter(|(_, present)| *present)
+        .map(|(name, _)| *name)
+        .collect()
+}
@@ -0,0 +1,58 @@
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use crate::ecs::utils::constants::{FRAME_GRAPH_SAMPLES, TIMING_SMOOTHING};
+
+/// Frame times for the debug overlay's graph, and how long each system takes.
+pub struct FrameStats {
+    /// Seconds per frame, oldest first.
+    frame_times: VecDeque<f32>,
+    /// Smoothed seconds per call, in the order the systems were first measured.
+    timings: Vec<(&'static str, f32)>,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        FrameStats {
+            frame_times: VecDeque::with_capacity(FRAME_GRAPH_SAMPLES),
+            timings: Vec::new(),
+        }
+    }
+
+    pub fn record_frame(&mut self, dt: f32) {
+        if self.frame_times.len() == FRAME_GRAPH_SAMPLES {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(dt);
+    }
+
+    /// Runs `system`, adding its duration to the timings under `name`.
+    pub fn measure<T>(&mut self, name: &'static str, system: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = system();
+        self.record(name, start.elapsed().as_secs_f32());
+        result
+    }
+
+    /// Adds a duration measured elsewhere to the timings under `name`.
+    pub fn record(&mut self, name: &'static str, elapsed: f32) {
+        match self.timings.iter_mut().find(|(timing, _)| *timing == name) {
+            Some((_, average)) => *average += (elapsed - *average) * TIMING_SMOOTHING,
+            None => self.timings.push((name, elapsed)),
+        }
+    }
+
+    pub fn frame_times(&self) -> impl Iterator<Item = f32> + '_ {
+        self.frame_times.iter().copied()
+    }
+
+    pub fn timings(&self) -> &[(&'static str, f32)] {
+        &self.timings
+    }
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        FrameStats::new()
+    }
+}
@@ -0,0 +1,123 @@
+use crate::ecs::{
+    game_state::EntityIndex,
+    systems::physics_system::physics::{Position, Size},
+    utils::scene_loader::SceneFile,
+};
+
+/// Something the editor can place.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PaletteItem {
+    Desk { desk_type: i32 },
+    Npc { id: u32, name: String },
+    Prop { frame: String },
+}
+
+impl PaletteItem {
+    pub fn label(&self) -> String {
+        match self {
+            PaletteItem::Desk { desk_type } => format!("Desk type {}", desk_type),
+            PaletteItem::Npc { name, .. } => format!("NPC {}", name),
+            PaletteItem::Prop { frame } => format!("Prop {}", frame),
+        }
+    }
+}
+
+/// A field of the selected entity shown in the side panel.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EditorField {
+    X,
+    Y,
+    Width,
+    Height,
+    DeskType,
+    Animation,
+    VoicePitch,
+    Frame,
+}
+
+impl EditorField {
+    pub fn label(self) -> &'static str {
+        match self {
+            EditorField::X => "x",
+            EditorField::Y => "y",
+            EditorField::Width => "width",
+            EditorField::Height => "height",
+            EditorField::DeskType => "desk type",
+            EditorField::Animation => "animation",
+            EditorField::VoicePitch => "voice pitch",
+            EditorField::Frame => "frame",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum Drag {
+    /// Moving an entity, `grab` is where it was picked up relative to its center.
+    Move { entity: EntityIndex, grab: Position },
+    /// Dragging the bottom right corner of an entity's collision box.
+    Resize { entity: EntityIndex },
+    /// Moving the view with the middle button, from where it was grabbed.
+    Pan { last: Position },
+}
+
+/// State of the level editor. Edits go straight to the office's components, the undo and
+/// redo stacks hold the whole layout from before each edit.
+pub struct Editor {
+    pub palette: Vec<PaletteItem>,
+    pub palette_index: usize,
+    pub selected: Option<EntityIndex>,
+    pub field_index: usize,
+    pub drag: Option<Drag>,
+    /// Layout before the current drag, pushed to the undo stack if the drag changes it.
+    pub drag_snapshot: Option<SceneFile>,
+    pub undo: Vec<SceneFile>,
+    pub redo: Vec<SceneFile>,
+    /// Positions and sizes snap to multiples of this, the floor tile size.
+    pub grid: Size,
+    /// Last thing that happened, shown under the palette.
+    pub status: String,
+}
+
+impl Editor {
+    pub fn new(palette: Vec<PaletteItem>, grid: Size) -> Self {
+        Editor {
+            palette,
+            palette_index: 0,
+            selected: None,
+            field_index: 0,
+            drag: None,
+            drag_snapshot: None,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            grid,
+            status: String::new(),
+        }
+    }
+
+    pub fn palette_item(&self) -> Option<&PaletteItem> {
+        self.palette.get(self.palette_index)
+    }
+
+    pub fn cycle_palette(&mut self, step: i32) {
+        if !self.palette.is_empty() {
+            let len = self.palette.len() as i32;
+            self.palette_index = (self.palette_index as i32 + step).rem_euclid(len) as usize;
+        }
+    }
+
+    /// Remembers `before` as the layout to go back to, forgetting anything undone.
+    pub fn push_undo(&mut self, before: SceneFile) {
+        if self.undo.last() != Some(&before) {
+            self.undo.push(before);
+            self.redo.clear();
+        }
+    }
+
+    pub fn snap(&self, value: f32, step: f32) -> f32 {
+        if step <= 0.0 {
+            value
+        } else {
+            (value / step).round() * step
+        }
+    }
+}
@@ -0,0 +1,495 @@
+use ggez::{
+    event::{KeyCode, KeyMods, MouseButton},
+    graphics::{self, Color, DrawMode, DrawParam, MeshBuilder, Rect},
+    input::keyboard,
+    Context, GameResult,
+};
+
+use super::editor::{Drag, Editor, EditorField, PaletteItem};
+use crate::ecs::{
+    game_state::{EntityIndex, GameState},
+    scenes::shared_state::SharedState,
+    systems::{
+        console_system::console::Console,
+        physics_system::physics::{Physics, Position},
+    },
+    utils::{
+        constants::{DESK_H, DESK_W, EDITOR_PAN_SPEED, EDITOR_PANEL_W, HUMANOID_H, HUMANOID_W},
+        npcs_json_loader::load_npcs,
+        scene_loader::{save_scene, SceneEntity, SceneEntityKind},
+    },
+};
+
+const SELECTION_COLOR: Color = Color::new(1.0, 1.0, 0.0, 1.0);
+const BOX_COLOR: Color = Color::new(1.0, 1.0, 1.0, 0.5);
+const PANEL_COLOR: Color = Color::new(0.0, 0.0, 0.0, 0.7);
+/// Size in screen pixels of the square at an entity's corner that resizes it.
+const HANDLE_SIZE: f32 = 8.0;
+
+pub fn register_console_commands(console: &mut Console) {
+    console.register("editor", "", "toggles the level editor", |_, shared, game, _| {
+        toggle_editor(shared, game);
+        Ok(format!("Editor {}", if game.editor.is_some() { "on" } else { "off" }))
+    });
+}
+
+/// Opens the editor with a palette of desks, the configured NPCs and the office atlas'
+/// other frames as props, or closes it.
+pub fn toggle_editor(shared: &mut SharedState, game: &mut GameState) {
+    if game.editor.take().is_some() {
+        game.camera.release();
+        return;
+    }
+
+    let mut palette: Vec<PaletteItem> = game
+        .desk_types()
+        .into_iter()
+        .map(|desk_type| PaletteItem::Desk { desk_type })
+        .collect();
+    match load_npcs(&shared.asset_manager, game.npcs_config()) {
+        Ok(npcs) => palette.extend(npcs.into_iter().map(|npc| PaletteItem::Npc { id: npc.id, name: npc.name })),
+        Err(error) => eprintln!("Couldn't load the NPCs for the editor palette: {}", error),
+    }
+    palette.extend(
+        game.prop_frames()
+            .into_iter()
+            .map(|frame| PaletteItem::Prop { frame }),
+    );
+
+    let mut editor = Editor::new(palette, game.tile_size());
+    editor.status = "Editing, F4 or Escape to leave".to_string();
+    game.editor = Some(editor);
+    game.camera.pan_to(game.camera.position, 0.0);
+}
+
+/// Scrolls the view with WASD while the player stands still.
+pub fn update_editor(ctx: &mut Context, game: &mut GameState) {
+    let dt = ggez::timer::delta(ctx).as_secs_f32();
+    let step = EDITOR_PAN_SPEED * dt / game.camera.zoom;
+    let mut target = game.camera.position;
+    if keyboard::is_key_pressed(ctx, KeyCode::W) {
+        target.y -= step;
+    }
+    if keyboard::is_key_pressed(ctx, KeyCode::S) {
+        target.y += step;
+    }
+    if keyboard::is_key_pressed(ctx, KeyCode::A) {
+        target.x -= step;
+    }
+    if keyboard::is_key_pressed(ctx, KeyCode::D) {
+        target.x += step;
+    }
+    game.camera.pan_to(target, 0.0);
+}
+
+pub fn editor_key_down(ctx: &mut Context, shared: &mut SharedState, game: &mut GameState, key: KeyCode) -> GameResult {
+    let mut editor = match game.editor.take() {
+        Some(editor) => editor,
+        None => return Ok(()),
+    };
+    let ctrl = keyboard::is_mod_active(ctx, KeyMods::CTRL);
+    let shift = keyboard::is_mod_active(ctx, KeyMods::SHIFT);
+
+    // The editor goes back into the game state even when an edit fails
+    let mut result = Ok(());
+    match key {
+        KeyCode::Z if ctrl && shift => result = redo(ctx, shared, game, &mut editor),
+        KeyCode::Z if ctrl => result = undo(ctx, shared, game, &mut editor),
+        KeyCode::Y if ctrl => result = redo(ctx, shared, game, &mut editor),
+        KeyCode::S if ctrl => {
+            let path = game.scene_path();
+            editor.status = match save_scene(&shared.asset_manager, &path, &game.scene_file()) {
+                Ok(()) => format!("Saved {}", shared.asset_manager.source().display(&path)),
+                Err(error) => format!("Couldn't save: {}", error),
+            };
+        }
+        KeyCode::LBracket => editor.cycle_palette(-1),
+        KeyCode::RBracket | KeyCode::Tab => editor.cycle_palette(1),
+        KeyCode::Delete | KeyCode::Back => {
+            if let Some(entity) = editor.selected.take() {
+                editor.push_undo(game.scene_file());
+                game.remove_entity(entity);
+                editor.status = format!("Deleted entity {}", entity);
+            }
+        }
+        KeyCode::Up => editor.field_index = editor.field_index.saturating_sub(1),
+        KeyCode::Down => {
+            let count = editor.selected.map_or(0, |entity| fields(game, entity).len());
+            editor.field_index = (editor.field_index + 1).min(count.saturating_sub(1));
+        }
+        KeyCode::Left | KeyCode::Right => {
+            let direction = if key == KeyCode::Left { -1.0 } else { 1.0 };
+            if let Some(entity) = editor.selected {
+                if let Some(field) = fields(game, entity).get(editor.field_index).copied() {
+                    editor.push_undo(game.scene_file());
+                    adjust_field(game, &editor, entity, field, direction);
+                }
+            }
+        }
+        _ => (),
+    }
+
+    game.editor = Some(editor);
+    result
+}
+
+pub fn editor_mouse_down(
+    ctx: &mut Context,
+    shared: &mut SharedState,
+    game: &mut GameState,
+    button: MouseButton,
+    screen_position: Position,
+) -> GameResult {
+    let mut editor = match game.editor.take() {
+        Some(editor) => editor,
+        None => return Ok(()),
+    };
+    let world_position = game.camera.screen_to_world(&screen_position);
+
+    let mut result = Ok(());
+    match button {
+        MouseButton::Left => {
+            let on_handle = editor.selected.filter(|entity| {
+                game.physics_components[*entity]
+                    .as_ref()
+                    .is_some_and(|physics| handle_rect(game, physics).contains([screen_position.x, screen_position.y]))
+            });
+
+            if let Some(entity) = on_handle {
+                editor.drag_snapshot = Some(game.scene_file());
+                editor.drag = Some(Drag::Resize { entity });
+            } else if let Some(entity) = game.entity_at(&world_position) {
+                let center = game.physics_components[entity].map_or(world_position, |physics| physics.position);
+                editor.selected = Some(entity);
+                editor.field_index = 0;
+                editor.drag_snapshot = Some(game.scene_file());
+                editor.drag = Some(Drag::Move {
+                    entity,
+                    grab: Position {
+                        x: world_position.x - center.x,
+                        y: world_position.y - center.y,
+                    },
+                });
+            } else if let Some(item) = editor.palette_item().cloned() {
+                let before = game.scene_file();
+                match place(ctx, shared, game, &editor, &item, world_position) {
+                    Ok(entity) => {
+                        editor.push_undo(before);
+                        editor.selected = entity;
+                        editor.field_index = 0;
+                        editor.status = format!("Placed {}", item.label());
+                    }
+                    Err(error) => result = Err(error),
+                }
+            }
+        }
+        MouseButton::Right => {
+            if let Some(entity) = game.entity_at(&world_position) {
+                editor.push_undo(game.scene_file());
+                game.remove_entity(entity);
+                if editor.selected == Some(entity) {
+                    editor.selected = None;
+                }
+                editor.status = format!("Deleted entity {}", entity);
+            }
+        }
+        MouseButton::Middle => editor.drag = Some(Drag::Pan { last: screen_position }),
+        _ => (),
+    }
+
+    game.editor = Some(editor);
+    result
+}
+
+pub fn editor_mouse_motion(game: &mut GameState, screen_position: Position) {
+    let mut editor = match game.editor.take() {
+        Some(editor) => editor,
+        None => return,
+    };
+    let world_position = game.camera.screen_to_world(&screen_position);
+
+    match editor.drag {
+        Some(Drag::Move { entity, grab }) => {
+            if let Some(physics) = game.physics_components[entity].as_mut() {
+                let x = world_position.x - grab.x - physics.size.w_half();
+                let y = world_position.y - grab.y - physics.size.h_half();
+                physics.position.x = editor.snap(x, editor.grid.width) + physics.size.w_half();
+                physics.position.y = editor.snap(y, editor.grid.height) + physics.size.h_half();
+            }
+        }
+        Some(Drag::Resize { entity }) => {
+            if let Some(physics) = game.physics_components[entity].as_mut() {
+                let left = physics.position.x - physics.size.w_half();
+                let top = physics.position.y - physics.size.h_half();
+                let width = editor.snap(world_position.x - left, editor.grid.width).max(editor.grid.width);
+                let height = editor.snap(world_position.y - top, editor.grid.height).max(editor.grid.height);
+                physics.size.width = width;
+                physics.size.height = height;
+                physics.position = Position {
+                    x: left + width / 2.0,
+                    y: top + height / 2.0,
+                };
+            }
+        }
+        Some(Drag::Pan { last }) => {
+            let zoom = game.camera.zoom;
+            let target = Position {
+                x: game.camera.position.x - (screen_position.x - last.x) / zoom,
+                y: game.camera.position.y - (screen_position.y - last.y) / zoom,
+            };
+            game.camera.pan_to(target, 0.0);
+            editor.drag = Some(Drag::Pan { last: screen_position });
+        }
+        None => (),
+    }
+
+    game.editor = Some(editor);
+}
+
+pub fn editor_mouse_up(game: &mut GameState) {
+    let mut editor = match game.editor.take() {
+        Some(editor) => editor,
+        None => return,
+    };
+    editor.drag = None;
+    if let Some(before) = editor.drag_snapshot.take() {
+        if before != game.scene_file() {
+            editor.push_undo(before);
+        }
+    }
+    game.editor = Some(editor);
+}
+
+/// Draws every collision box, the selection with its resize handle, the side panel with
+/// the selected entity's fields and the palette.
+pub fn draw_editor(ctx: &mut Context, shared: &SharedState, game: &GameState) -> GameResult {
+    let editor = match &game.editor {
+        Some(editor) => editor,
+        None => return Ok(()),
+    };
+    let screen = shared.viewport.virtual_size;
+
+    let mut mesh = MeshBuilder::new();
+    for (index, physics) in game.physics_components.iter().enumerate() {
+        if let Some(physics) = physics {
+            let color = if editor.selected == Some(index) { SELECTION_COLOR } else { BOX_COLOR };
+            mesh.rectangle(DrawMode::stroke(1.0), screen_rect(game, physics), color)?;
+        }
+    }
+    if let Some(physics) = editor.selected.and_then(|entity| game.physics_components[entity].as_ref()) {
+        mesh.rectangle(DrawMode::fill(), handle_rect(game, physics), SELECTION_COLOR)?;
+    }
+    let panel = Rect::new(screen.width - EDITOR_PANEL_W, 0.0, EDITOR_PANEL_W, screen.height);
+    mesh.rectangle(DrawMode::fill(), panel, PANEL_COLOR)?;
+    let mesh = mesh.build(ctx)?;
+    graphics::draw(ctx, &mesh, DrawParam::default())?;
+
+    let mut lines = vec!["Level editor".to_string(), String::new()];
+    match editor.selected {
+        Some(entity) => {
+            lines.push(format!("Entity {}", entity));
+            for (index, field) in fields(game, entity).into_iter().enumerate() {
+                let cursor = if index == editor.field_index { ">" } else { " " };
+                lines.push(format!("{} {}: {}", cursor, field.label(), field_value(game, entity, field)));
+            }
+        }
+        None => lines.push("Nothing selected".to_string()),
+    }
+    lines.push(String::new());
+    lines.push(format!(
+        "Palette {}/{}",
+        editor.palette_index + 1,
+        editor.palette.len()
+    ));
+    lines.push(editor.palette_item().map_or_else(String::new, PaletteItem::label));
+    lines.push(String::new());
+    lines.extend(
+        [
+            "Click: place / select, drag: move",
+            "Corner: resize, right click: delete",
+            "[ ]: palette, Up/Down/Left/Right: fields",
+            "WASD, middle drag: scroll",
+            "Ctrl+Z / Ctrl+Y: undo / redo",
+            "Ctrl+S: save, F4 / Escape: leave",
+        ]
+        .iter()
+        .map(|line| line.to_string()),
+    );
+    lines.push(String::new());
+    lines.push(editor.status.clone());
+
+    let font = shared.asset_manager.ui_font();
+    let line_height = font.line_height(ctx);
+    let mut y = 8.0;
+    for line in lines {
+        font.draw_text(ctx, &shared.asset_manager, &line, [panel.x + 8.0, y], Color::WHITE)?;
+        y += line_height;
+    }
+    Ok(())
+}
+
+fn undo(ctx: &mut Context, shared: &mut SharedState, game: &mut GameState, editor: &mut Editor) -> GameResult {
+    if let Some(before) = editor.undo.pop() {
+        editor.redo.push(game.scene_file());
+        game.load_scene(ctx, shared, &before)?;
+        editor.selected = None;
+        editor.status = "Undone".to_string();
+    }
+    Ok(())
+}
+
+fn redo(ctx: &mut Context, shared: &mut SharedState, game: &mut GameState, editor: &mut Editor) -> GameResult {
+    if let Some(after) = editor.redo.pop() {
+        editor.undo.push(game.scene_file());
+        game.load_scene(ctx, shared, &after)?;
+        editor.selected = None;
+        editor.status = "Redone".to_string();
+    }
+    Ok(())
+}
+
+/// Adds `item` with the top left corner of its box snapped to the grid near `position`.
+fn place(
+    ctx: &mut Context,
+    shared: &mut SharedState,
+    game: &mut GameState,
+    editor: &Editor,
+    item: &PaletteItem,
+    position: Position,
+) -> GameResult<Option<EntityIndex>> {
+    let (kind, width, height) = match item {
+        PaletteItem::Desk { desk_type } => (
+            SceneEntityKind::Desk {
+                desk_type: *desk_type,
+                animation_id: game.desk_components.iter().flatten().count() as i32 + 2,
+            },
+            DESK_W,
+            DESK_H,
+        ),
+        PaletteItem::Npc { id, .. } => (SceneEntityKind::Npc { id: *id }, HUMANOID_W, HUMANOID_H),
+        PaletteItem::Prop { frame } => {
+            let (width, height) = game.prop_size(frame);
+            (SceneEntityKind::Prop { frame: frame.clone() }, width, height)
+        }
+    };
+    let x = editor.snap(position.x - width / 2.0, editor.grid.width) + width / 2.0;
+    let y = editor.snap(position.y - height / 2.0, editor.grid.height) + height / 2.0;
+
+    let npcs = load_npcs(&shared.asset_manager, game.npcs_config())?;
+    game.spawn_scene_entity(ctx, shared, &SceneEntity { x, y, width, height, kind, interactable: None }, &npcs)
+}
+
+fn fields(game: &GameState, entity: EntityIndex) -> Vec<EditorField> {
+    let mut fields = vec![EditorField::X, EditorField::Y, EditorField::Width, EditorField::Height];
+    if game.desk_components[entity].is_some() {
+        fields.extend([EditorField::DeskType, EditorField::Animation]);
+    }
+    if game.npcs_components[entity].is_some() {
+        fields.push(EditorField::VoicePitch);
+    }
+    if game.prop_components[entity].is_some() {
+        fields.push(EditorField::Frame);
+    }
+    fields
+}
+
+fn field_value(game: &GameState, entity: EntityIndex, field: EditorField) -> String {
+    let physics = game.physics_components[entity];
+    let desk = game.desk_components[entity];
+    match field {
+        EditorField::X => physics.map_or(String::new(), |physics| format!("{:.0}", physics.position.x)),
+        EditorField::Y => physics.map_or(String::new(), |physics| format!("{:.0}", physics.position.y)),
+        EditorField::Width => physics.map_or(String::new(), |physics| format!("{:.0}", physics.size.width)),
+        EditorField::Height => physics.map_or(String::new(), |physics| format!("{:.0}", physics.size.height)),
+        EditorField::DeskType => desk.map_or(String::new(), |desk| desk.desk_type.to_string()),
+        EditorField::Animation => desk.map_or(String::new(), |desk| desk.animation_id.to_string()),
+        EditorField::VoicePitch => game.npcs_components[entity]
+            .as_ref()
+            .map_or(String::new(), |npc| format!("{:.1}", npc.voice_pitch)),
+        EditorField::Frame => game.prop_components[entity]
+            .as_ref()
+            .map_or(String::new(), |prop| prop.frame.clone()),
+    }
+}
+
+/// Steps a field: positions and sizes by one grid cell, the rest by one value.
+fn adjust_field(game: &mut GameState, editor: &Editor, entity: EntityIndex, field: EditorField, direction: f32) {
+    let grid = editor.grid;
+    if let Some(physics) = game.physics_components[entity].as_mut() {
+        match field {
+            EditorField::X => physics.position.x += direction * grid.width,
+            EditorField::Y => physics.position.y += direction * grid.height,
+            EditorField::Width => {
+                let width = (physics.size.width + direction * grid.width).max(grid.width);
+                physics.position.x += (width - physics.size.width) / 2.0;
+                physics.size.width = width;
+            }
+            EditorField::Height => {
+                let height = (physics.size.height + direction * grid.height).max(grid.height);
+                physics.position.y += (height - physics.size.height) / 2.0;
+                physics.size.height = height;
+            }
+            _ => (),
+        }
+    }
+
+    match field {
+        EditorField::DeskType => {
+            let desk_types = game.desk_types();
+            if let Some(desk) = game.desk_components[entity].as_mut() {
+                desk.desk_type = cycle(&desk_types, &desk.desk_type, direction);
+            }
+        }
+        EditorField::Animation => {
+            if let Some(desk) = game.desk_components[entity].as_mut() {
+                desk.animation_id = (desk.animation_id + direction as i32).max(0);
+            }
+        }
+        EditorField::VoicePitch => {
+            if let Some(npc) = game.npcs_components[entity].as_mut() {
+                npc.voice_pitch = ((npc.voice_pitch + direction * 0.1) * 10.0).round().clamp(5.0, 20.0) / 10.0;
+                if let Some(emitter) = game.emitter_components[entity].as_mut() {
+                    emitter.pitch = npc.voice_pitch;
+                }
+            }
+        }
+        EditorField::Frame => {
+            let frames = game.prop_frames();
+            if let Some(prop) = game.prop_components[entity].as_mut() {
+                prop.frame = cycle(&frames, &prop.frame, direction);
+            }
+        }
+        _ => (),
+    }
+}
+
+fn cycle<T: Clone + PartialEq>(values: &[T], current: &T, direction: f32) -> T {
+    match values.iter().position(|value| value == current) {
+        Some(index) => {
+            let len = values.len() as i32;
+            values[(index as i32 + direction as i32).rem_euclid(len) as usize].clone()
+        }
+        None => values.first().cloned().unwrap_or_else(|| current.clone()),
+    }
+}
+
+fn screen_rect(game: &GameState, physics: &Physics) -> Rect {
+    let camera = &game.camera;
+    let position = camera.world_to_screen(&physics.position);
+    Rect::new(
+        position.x - physics.size.w_half() * camera.zoom,
+        position.y - physics.size.h_half() * camera.zoom,
+        physics.size.width * camera.zoom,
+        physics.size.height * camera.zoom,
+    )
+}
+
+fn handle_rect(game: &GameState, physics: &Physics) -> Rect {
+    let rect = screen_rect(game, physics);
+    Rect::new(
+        rect.right() - HANDLE_SIZE / 2.0,
+        rect.bottom() - HANDLE_SIZE / 2.0,
+        HANDLE_SIZE,
+        HANDLE_SIZE,
+    )
+}
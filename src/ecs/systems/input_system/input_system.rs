@@ -2,52 +2,93 @@ use ggez::*;
 use ggez::{event::KeyCode, input::keyboard};
 
 use crate::ecs::{
-    game_state::GameState, systems::input_system::interaction::Interaction,
+    game_state::GameState,
+    systems::input_system::interaction::Interaction,
+    ui::dialog_ui::Speaker,
+    utils::{
+        localization::Localization,
+        settings::{Action, Controls},
+    },
 };
 
-pub fn player_movements(ctx: &mut Context) -> Vec<KeyCode> {
-    let player_mov_keys = [KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right];
+/// UI sounds of the dialogue menu.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InteractionSound {
+    Move,
+    Select,
+    Back,
+}
+
+impl InteractionSound {
+    pub fn name(self) -> &'static str {
+        match self {
+            InteractionSound::Move => "ui_move",
+            InteractionSound::Select => "ui_select",
+            InteractionSound::Back => "ui_back",
+        }
+    }
+}
+
+pub fn player_movements(ctx: &mut Context, controls: &Controls) -> Vec<KeyCode> {
+    let player_mov_actions = [Action::MoveUp, Action::MoveDown, Action::MoveLeft, Action::MoveRight];
     let mut pressed_mov_keys: Vec<KeyCode> = Vec::new();
 
-    for key in player_mov_keys {
-        if keyboard::is_key_pressed(ctx, key) {
-            pressed_mov_keys.push(key);
+    for action in player_mov_actions {
+        if controls.keys(action).into_iter().any(|key| keyboard::is_key_pressed(ctx, key)) {
+            pressed_mov_keys.push(action.canonical_key());
         }
     }
     pressed_mov_keys
 }
 
 // Interactions
-pub fn key_down_event_interaction(game_state: &GameState, key: KeyCode) -> Option<Interaction> {
-    match game_state.current_interaction {
-        Some(_) => interaction_input_handler(game_state, key),
-        None => match key {
-            KeyCode::Return => begin_interaction(game_state),
-            _ => None,
-        },
+/// Who's talking and what they say when the player interacts with the focused entity: an
+/// NPC's dialogue, or an object's text once it has been used. A light follows its entity's
+/// toggle.
+pub fn begin_interaction(game_state: &mut GameState, localization: &Localization) -> Option<(Speaker, Interaction)> {
+    let index = game_state.player_physics.current_focus?;
+    if let Some(npc) = &game_state.npcs_components[index] {
+        let interaction = game_state.npcs_interactions[index].clone()?;
+        return Some((Speaker::npc(npc), interaction));
     }
+
+    let interactable = game_state.interactable_components[index].as_mut()?;
+    let interaction = interactable.interact(&mut game_state.inventory, &mut game_state.flags, localization);
+    if let (Some(on), Some(light)) = (interactable.is_on(), game_state.light_components[index].as_mut()) {
+        light.on = on;
+    }
+    Some((Speaker::object(localization.tr(&interactable.name)), interaction))
 }
 
-fn interaction_input_handler(game_state: &GameState, key: KeyCode) -> Option<Interaction> {
+/// Next state of an ongoing interaction, `None` once it's over.
+pub fn interaction_input_handler(interaction: &Interaction, key: KeyCode) -> Option<Interaction> {
     match key {
-        KeyCode::Up | KeyCode::Down | KeyCode::Return => update_interaction(game_state, key),
+        KeyCode::Up | KeyCode::Down | KeyCode::Return => update_interaction(interaction, key),
         KeyCode::Escape => None,
         _ => None,
     }
 }
 
-fn begin_interaction(game_state: &GameState) -> Option<Interaction> {
-    match game_state.player_physics.current_focus {
-        Some(focused_entity_id) => match &game_state.npcs_components[focused_entity_id] {
-            Some(_) => game_state.npcs_interactions[focused_entity_id].clone(),
-            None => None,
-        },
-        None => None,
+/// Sound for going from one interaction state to the next, as returned by `update_interaction`.
+pub fn interaction_sound(
+    before: &Option<Interaction>,
+    after: &Option<Interaction>,
+) -> Option<InteractionSound> {
+    match (before, after) {
+        (None, Some(_)) => Some(InteractionSound::Select),
+        (Some(_), None) => Some(InteractionSound::Back),
+        (Some(before), Some(after)) if before.dialog != after.dialog => {
+            Some(InteractionSound::Select)
+        }
+        (Some(before), Some(after)) if before.hovered_option != after.hovered_option => {
+            Some(InteractionSound::Move)
+        }
+        _ => None,
     }
 }
 
-fn update_interaction(game_state: &GameState, action: KeyCode) -> Option<Interaction> {
-    let mut interaction = game_state.current_interaction.clone().unwrap();
+fn update_interaction(interaction: &Interaction, action: KeyCode) -> Option<Interaction> {
+    let mut interaction = interaction.clone();
     match &interaction.sub_interactions {
         Some(sub_interactions) => match action {
             KeyCode::Up => {
@@ -57,12 +98,14 @@ fn update_interaction(game_state: &GameState, action: KeyCode) -> Option<Interac
                 Some(interaction)
             }
             KeyCode::Down => {
-                if interaction.hovered_option < interaction.options.as_ref().unwrap().len() - 1 {
+                // Options without a sub interaction can't be picked
+                let option_count = interaction.options.as_ref().map_or(0, Vec::len);
+                if interaction.hovered_option + 1 < option_count.min(sub_interactions.len()) {
                     interaction.hovered_option += 1;
                 }
                 Some(interaction)
             }
-            KeyCode::Return => Some(sub_interactions[interaction.hovered_option].clone()),
+            KeyCode::Return => sub_interactions.get(interaction.hovered_option).cloned(),
             _ => None,
         },
         None => None,
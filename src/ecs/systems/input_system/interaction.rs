@@ -1,10 +1,10 @@
-#[derive(Clone, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct Interaction {
-    #[serde(default)]
+    #[serde(default, skip_serializing)]
     pub hovered_option: usize,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub options: Option<Vec<String>>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub sub_interactions: Option<Vec<Interaction>>,
     #[serde(default)]
     pub dialog: String,
@@ -25,6 +25,73 @@ impl Interaction {
     }
 }
 
+impl Interaction {
+    /// Every dialog and option string of this interaction tree, which are localization keys.
+    pub fn text_keys(&self) -> Vec<String> {
+        let mut keys = vec![self.dialog.clone()];
+        if let Some(options) = &self.options {
+            keys.extend(options.iter().cloned());
+        }
+        if let Some(sub_interactions) = &self.sub_interactions {
+            for sub_interaction in sub_interactions {
+                keys.extend(sub_interaction.text_keys());
+            }
+        }
+        keys
+    }
+
+    /// The interaction reached by picking the options at `path` in turn, e.g. `[1, 0]`.
+    pub fn at_path(&self, path: &[usize]) -> Option<&Interaction> {
+        match path.split_first() {
+            Some((index, rest)) => self.sub_interactions.as_ref()?.get(*index)?.at_path(rest),
+            None => Some(self),
+        }
+    }
+
+    pub fn at_path_mut(&mut self, path: &[usize]) -> Option<&mut Interaction> {
+        match path.split_first() {
+            Some((index, rest)) => self.sub_interactions.as_mut()?.get_mut(*index)?.at_path_mut(rest),
+            None => Some(self),
+        }
+    }
+
+    /// Mistakes in the structure of this tree, each prefixed with where it is, `path` being
+    /// where this interaction is, e.g. `main_interaction.sub_interactions[1]`.
+    pub fn problems(&self, path: &str) -> Vec<String> {
+        let mut problems = self.own_problems(path);
+        for (index, sub_interaction) in self.sub_interactions.iter().flatten().enumerate() {
+            problems.extend(sub_interaction.problems(&format!("{}.sub_interactions[{}]", path, index)));
+        }
+        problems
+    }
+
+    /// Like `problems`, without looking into the sub interactions.
+    pub fn own_problems(&self, path: &str) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.dialog.trim().is_empty() {
+            problems.push(format!("{}.dialog: empty", path));
+        }
+
+        let option_count = self.options.as_ref().map_or(0, Vec::len);
+        let sub_count = self.sub_interactions.as_ref().map_or(0, Vec::len);
+        if option_count != sub_count {
+            problems.push(format!(
+                "{}: {} options but {} sub_interactions",
+                path, option_count, sub_count
+            ));
+        }
+        if self.options.as_ref().is_some_and(Vec::is_empty) {
+            problems.push(format!("{}.options: empty, leave it out instead", path));
+        }
+        for (index, option) in self.options.iter().flatten().enumerate() {
+            if option.trim().is_empty() {
+                problems.push(format!("{}.options[{}]: empty", path, index));
+            }
+        }
+        problems
+    }
+}
+
 impl Default for Interaction {
     fn default() -> Interaction {
         Interaction {
@@ -35,3 +102,75 @@ impl Default for Interaction {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Interaction;
+
+    fn line(dialog: &str) -> Interaction {
+        Interaction::new(None, None, dialog.to_string())
+    }
+
+    fn options(keys: &[&str]) -> Option<Vec<String>> {
+        Some(keys.iter().map(|key| key.to_string()).collect())
+    }
+
+    #[test]
+    fn a_complete_tree_has_no_problems() {
+        let interaction = Interaction::new(
+            options(&["option.a", "option.b"]),
+            Some(vec![line("dialog.a"), line("dialog.b")]),
+            "dialog.hi".to_string(),
+        );
+        assert!(interaction.problems("main_interaction").is_empty());
+    }
+
+    #[test]
+    fn options_without_sub_interactions() {
+        let interaction = Interaction::new(options(&["option.a"]), None, "dialog.hi".to_string());
+        assert_eq!(
+            interaction.problems("npcs[0].main_interaction"),
+            vec!["npcs[0].main_interaction: 1 options but 0 sub_interactions"]
+        );
+    }
+
+    #[test]
+    fn mismatched_lengths_are_reported_where_they_are() {
+        let nested = Interaction::new(
+            options(&["option.c"]),
+            Some(vec![line("dialog.c"), line("dialog.d")]),
+            "dialog.b".to_string(),
+        );
+        let interaction = Interaction::new(
+            options(&["option.a", "option.b"]),
+            Some(vec![line("dialog.a"), nested]),
+            "dialog.hi".to_string(),
+        );
+        assert_eq!(
+            interaction.problems("npcs[2].main_interaction"),
+            vec!["npcs[2].main_interaction.sub_interactions[1]: 1 options but 2 sub_interactions"]
+        );
+    }
+
+    #[test]
+    fn an_empty_options_list() {
+        let interaction = Interaction::new(Some(Vec::new()), None, "dialog.hi".to_string());
+        assert_eq!(
+            interaction.problems("main_interaction"),
+            vec!["main_interaction.options: empty, leave it out instead"]
+        );
+    }
+
+    #[test]
+    fn empty_dialogs_and_options() {
+        let interaction = Interaction::new(options(&[" "]), Some(vec![line("")]), String::new());
+        assert_eq!(
+            interaction.problems("main_interaction"),
+            vec![
+                "main_interaction.dialog: empty",
+                "main_interaction.options[0]: empty",
+                "main_interaction.sub_interactions[0].dialog: empty",
+            ]
+        );
+    }
+}
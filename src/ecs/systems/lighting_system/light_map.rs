@@ -0,0 +1,226 @@
+use ggez::{
+    conf::NumSamples,
+    graphics::{self, BlendMode, Canvas, Color, DrawParam, Drawable, FilterMode, Image},
+    Context, GameResult,
+};
+
+use crate::ecs::{
+    systems::{physics_system::physics::Size, render_system::viewport::Viewport},
+    utils::constants::{LIGHT_GRADIENT_SIZE, LIGHT_MAP_CPU_CELL},
+};
+
+/// A light where it's drawn, in virtual resolution pixels.
+#[derive(Copy, Clone, Debug)]
+pub struct ScreenLight {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+    /// Already scaled by the light's intensity.
+    pub color: Color,
+}
+
+/// How much of a light reaches `t` of the way from its center to its radius.
+pub fn falloff(t: f32) -> f32 {
+    let remaining = (1.0 - t).max(0.0);
+    remaining * remaining
+}
+
+/// The light reaching each cell of a `width` by `height` grid of `cell` pixel cells, as
+/// RGBA bytes: the ambient light plus every light, saturating at white. Needs no graphics
+/// context, so it also runs without a window.
+pub fn light_map_pixels(ambient: Color, lights: &[ScreenLight], width: usize, height: usize, cell: f32) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for row in 0..height {
+        let y = (row as f32 + 0.5) * cell;
+        for column in 0..width {
+            let x = (column as f32 + 0.5) * cell;
+            let (mut r, mut g, mut b) = (ambient.r, ambient.g, ambient.b);
+            for light in lights.iter() {
+                let distance = ((x - light.x).powi(2) + (y - light.y).powi(2)).sqrt();
+                if distance < light.radius {
+                    let amount = falloff(distance / light.radius);
+                    r += light.color.r * amount;
+                    g += light.color.g * amount;
+                    b += light.color.b * amount;
+                }
+            }
+            pixels.extend_from_slice(&[to_byte(r), to_byte(g), to_byte(b), 255]);
+        }
+    }
+    pixels
+}
+
+fn to_byte(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// A white disc fading out towards its edge, drawn once per light on the GPU.
+fn gradient_pixels(size: u16) -> Vec<u8> {
+    let half = size as f32 / 2.0;
+    let mut pixels = Vec::with_capacity(size as usize * size as usize * 4);
+    for row in 0..size {
+        for column in 0..size {
+            let dx = column as f32 + 0.5 - half;
+            let dy = row as f32 + 0.5 - half;
+            let alpha = falloff((dx * dx + dy * dy).sqrt() / half);
+            pixels.extend_from_slice(&[255, 255, 255, to_byte(alpha)]);
+        }
+    }
+    pixels
+}
+
+/// The light falling on the world, multiplied over it once the world is drawn. Lights are
+/// added up into an offscreen canvas on the GPU, or on the CPU where canvases aren't
+/// available.
+pub enum LightMap {
+    Gpu {
+        canvas: Canvas,
+        gradient: Image,
+        size: Size,
+    },
+    Cpu,
+}
+
+impl LightMap {
+    /// A GPU light map, or the CPU one if the canvas can't be made.
+    pub fn new(ctx: &mut Context, size: Size) -> Self {
+        match LightMap::gpu(ctx, size) {
+            Ok(light_map) => light_map,
+            Err(error) => {
+                eprintln!("Lighting on the CPU, couldn't make the light map canvas: {}", error);
+                LightMap::Cpu
+            }
+        }
+    }
+
+    pub fn gpu(ctx: &mut Context, size: Size) -> GameResult<Self> {
+        let mut canvas = Canvas::new(
+            ctx,
+            size.width as u16,
+            size.height as u16,
+            NumSamples::One,
+            graphics::get_window_color_format(ctx),
+        )?;
+        canvas.set_blend_mode(Some(BlendMode::Multiply));
+
+        let mut gradient = Image::from_rgba8(
+            ctx,
+            LIGHT_GRADIENT_SIZE,
+            LIGHT_GRADIENT_SIZE,
+            &gradient_pixels(LIGHT_GRADIENT_SIZE),
+        )?;
+        gradient.set_filter(FilterMode::Linear);
+        gradient.set_blend_mode(Some(BlendMode::Add));
+        Ok(LightMap::Gpu { canvas, gradient, size })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LightMap::Gpu { .. } => "gpu",
+            LightMap::Cpu => "cpu",
+        }
+    }
+
+    /// Darkens what was drawn so far to the ambient light, brightened around `lights`.
+    pub fn draw(&mut self, ctx: &mut Context, viewport: &Viewport, ambient: Color, lights: &[ScreenLight]) -> GameResult {
+        let size = viewport.virtual_size;
+        if matches!(self, LightMap::Gpu { size: canvas_size, .. } if *canvas_size != size) {
+            *self = LightMap::new(ctx, size);
+        }
+
+        match self {
+            LightMap::Gpu { canvas, gradient, .. } => {
+                graphics::set_canvas(ctx, Some(canvas));
+                graphics::clear(ctx, ambient);
+                let scale = |radius: f32| 2.0 * radius / LIGHT_GRADIENT_SIZE as f32;
+                for light in lights.iter() {
+                    let params = DrawParam::default()
+                        .dest([light.x - light.radius, light.y - light.radius])
+                        .scale([scale(light.radius), scale(light.radius)])
+                        .color(light.color);
+                    graphics::draw(ctx, gradient, params)?;
+                }
+                // Back to the world's target
+                viewport.begin(ctx)?;
+                graphics::draw(ctx, canvas, DrawParam::default())
+            }
+            LightMap::Cpu => {
+                let width = (size.width / LIGHT_MAP_CPU_CELL).ceil() as usize;
+                let height = (size.height / LIGHT_MAP_CPU_CELL).ceil() as usize;
+                let pixels = light_map_pixels(ambient, lights, width, height, LIGHT_MAP_CPU_CELL);
+                let mut image = Image::from_rgba8(ctx, width as u16, height as u16, &pixels)?;
+                image.set_filter(FilterMode::Linear);
+                image.set_blend_mode(Some(BlendMode::Multiply));
+                graphics::draw(
+                    ctx,
+                    &image,
+                    DrawParam::default().scale([LIGHT_MAP_CPU_CELL, LIGHT_MAP_CPU_CELL]),
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AMBIENT: Color = Color::new(0.2, 0.3, 0.4, 1.0);
+
+    fn light(x: f32, y: f32, radius: f32, color: Color) -> ScreenLight {
+        ScreenLight { x, y, radius, color }
+    }
+
+    /// The RGBA bytes of a cell.
+    fn cell(pixels: &[u8], width: usize, column: usize, row: usize) -> [u8; 4] {
+        let start = (row * width + column) * 4;
+        [pixels[start], pixels[start + 1], pixels[start + 2], pixels[start + 3]]
+    }
+
+    fn ambient_bytes() -> [u8; 4] {
+        [to_byte(AMBIENT.r), to_byte(AMBIENT.g), to_byte(AMBIENT.b), 255]
+    }
+
+    #[test]
+    fn falloff_goes_from_full_to_nothing() {
+        assert_eq!(falloff(0.0), 1.0);
+        assert_eq!(falloff(0.5), 0.25);
+        assert_eq!(falloff(1.0), 0.0);
+        assert_eq!(falloff(2.0), 0.0);
+        assert!(falloff(0.2) > falloff(0.4));
+    }
+
+    #[test]
+    fn no_lights_give_the_ambient_light_everywhere() {
+        let pixels = light_map_pixels(AMBIENT, &[], 4, 3, 10.0);
+        assert_eq!(pixels.len(), 4 * 3 * 4);
+        for chunk in pixels.chunks(4) {
+            assert_eq!(chunk, ambient_bytes());
+        }
+    }
+
+    #[test]
+    fn a_light_is_brightest_at_its_center_and_ambient_at_its_radius() {
+        // Cells are 1 pixel wide, so cell 5 is centered on the light
+        let lights = [light(5.5, 0.5, 4.0, Color::new(0.6, 0.6, 0.6, 1.0))];
+        let pixels = light_map_pixels(AMBIENT, &lights, 11, 1, 1.0);
+
+        let center = cell(&pixels, 11, 5, 0);
+        assert_eq!(center, [to_byte(0.2 + 0.6), to_byte(0.3 + 0.6), to_byte(0.4 + 0.6), 255]);
+        let halfway = cell(&pixels, 11, 7, 0);
+        assert!(halfway[0] > ambient_bytes()[0] && halfway[0] < center[0]);
+        assert_eq!(cell(&pixels, 11, 3, 0), halfway);
+        // Cells 1 and 9 are exactly at the radius
+        for column in [0, 1, 9, 10] {
+            assert_eq!(cell(&pixels, 11, column, 0), ambient_bytes());
+        }
+    }
+
+    #[test]
+    fn channels_stop_at_white() {
+        let bright = Color::new(1.0, 0.8, 1.0, 1.0);
+        let lights = [light(0.5, 0.5, 10.0, bright), light(0.5, 0.5, 10.0, bright)];
+        let pixels = light_map_pixels(Color::new(0.9, 0.0, 0.9, 1.0), &lights, 1, 1, 1.0);
+        assert_eq!(pixels, vec![255, 255, 255, 255]);
+    }
+}
@@ -0,0 +1,176 @@
+use ggez::graphics::Color;
+
+use super::light_map::{LightMap, ScreenLight};
+use crate::ecs::{
+    components::{desk::Desk, light::Light},
+    sprites::office_sprite::animation_sprite_index,
+    systems::{
+        console_system::console::Console,
+        physics_system::physics::{Physics, Position},
+        render_system::camera::Camera,
+        schedule_system::clock::parse_clock_time,
+    },
+    utils::constants::MINUTES_PER_DAY,
+};
+
+/// How the office is lit through the day, from `lighting.json`.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct LightingConfig {
+    /// Ambient light at times of the day, blended in between.
+    #[serde(default = "default_ambient")]
+    pub ambient: Vec<AmbientJson>,
+    /// Lights fixed to the world rather than to an entity, in every area. An area's own are
+    /// in `areas.json`.
+    #[serde(default)]
+    pub lights: Vec<LightJson>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct AmbientJson {
+    /// `HH:MM`.
+    pub at: String,
+    pub color: [f32; 3],
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct LightJson {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+    pub color: [f32; 3],
+    #[serde(default = "default_intensity")]
+    pub intensity: f32,
+    /// Daylight coming in, as bright as the ambient light and dark at night.
+    #[serde(default)]
+    pub window: bool,
+}
+
+fn default_intensity() -> f32 {
+    1.0
+}
+
+fn default_ambient() -> Vec<AmbientJson> {
+    [
+        ("00:00", [0.2, 0.22, 0.4]),
+        ("06:00", [0.45, 0.42, 0.55]),
+        ("08:00", [1.0, 1.0, 1.0]),
+        ("17:00", [1.0, 0.95, 0.88]),
+        ("19:00", [0.7, 0.5, 0.45]),
+        ("21:00", [0.25, 0.27, 0.45]),
+    ]
+    .iter()
+    .map(|(at, color)| AmbientJson {
+        at: at.to_string(),
+        color: *color,
+    })
+    .collect()
+}
+
+impl Default for LightingConfig {
+    fn default() -> Self {
+        LightingConfig {
+            ambient: default_ambient(),
+            lights: Vec::new(),
+        }
+    }
+}
+
+pub fn register_console_commands(console: &mut Console) {
+    console.register("lighting", "[gpu|cpu|off]", "switches how the light map is drawn", |ctx, shared, game, args| {
+        match args.first() {
+            Some(&"gpu") => {
+                game.light_map = Some(LightMap::gpu(ctx, shared.viewport.virtual_size).map_err(|error| error.to_string())?)
+            }
+            Some(&"cpu") => game.light_map = Some(LightMap::Cpu),
+            Some(&"off") => game.light_map = None,
+            Some(other) => return Err(format!("expected gpu, cpu or off, got {}", other)),
+            None => {}
+        }
+        Ok(format!("Lighting {}", game.light_map.as_ref().map_or("off", |light_map| light_map.name())))
+    });
+}
+
+/// The ambient light at `minute`, blended between the config's times around it. Times that
+/// don't parse are left out.
+pub fn ambient_at(config: &LightingConfig, minute: f32) -> Color {
+    let mut keys: Vec<(f32, [f32; 3])> = config
+        .ambient
+        .iter()
+        .filter_map(|ambient| Some((parse_clock_time(&ambient.at)?, ambient.color)))
+        .collect();
+    keys.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+    let (first, last) = match (keys.first(), keys.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return Color::WHITE,
+    };
+    // Before the first time of the day it's still blending in from the last one
+    let before = keys.iter().rev().find(|(at, _)| *at <= minute).copied().unwrap_or(last);
+    let after = keys.iter().find(|(at, _)| *at > minute).copied().unwrap_or(first);
+
+    let span = (after.0 - before.0).rem_euclid(MINUTES_PER_DAY);
+    let t = if span > 0.0 {
+        (minute - before.0).rem_euclid(MINUTES_PER_DAY) / span
+    } else {
+        0.0
+    };
+    let channel = |index: usize| before.1[index] + (after.1[index] - before.1[index]) * t;
+    Color::new(channel(0), channel(1), channel(2), 1.0)
+}
+
+/// Every light that reaches the screen: the fixed ones, with windows as bright as
+/// `daylight`, and the entities', with monitors dimming as their desk's screen animates.
+pub fn screen_lights<'a>(
+    camera: &Camera,
+    fixed_lights: impl Iterator<Item = &'a LightJson>,
+    daylight: f32,
+    frames: usize,
+    physics_components: &[Option<Physics>],
+    light_components: &[Option<Light>],
+    desk_components: &[Option<Desk>],
+) -> Vec<ScreenLight> {
+    let mut lights = Vec::new();
+    let mut add = |position: Position, radius: f32, color: Color, intensity: f32| {
+        let position = camera.world_to_screen(&position);
+        let radius = radius * camera.zoom;
+        let on_screen = position.x + radius >= 0.0
+            && position.y + radius >= 0.0
+            && position.x - radius <= camera.size.width
+            && position.y - radius <= camera.size.height;
+        if on_screen && intensity > 0.0 {
+            lights.push(ScreenLight {
+                x: position.x,
+                y: position.y,
+                radius,
+                color: Color::new(color.r * intensity, color.g * intensity, color.b * intensity, 1.0),
+            });
+        }
+    };
+
+    for light in fixed_lights {
+        let intensity = if light.window { light.intensity * daylight } else { light.intensity };
+        let [r, g, b] = light.color;
+        add(Position { x: light.x, y: light.y }, light.radius, Color::new(r, g, b, 1.0), intensity);
+    }
+    for (index, light) in light_components.iter().enumerate() {
+        let (light, physics) = match (light, &physics_components[index]) {
+            (Some(light), Some(physics)) if light.on => (light, physics),
+            _ => continue,
+        };
+        let flicker = match &desk_components[index] {
+            Some(desk) if animation_sprite_index(frames, desk.animation_id) == 1 => 0.75,
+            _ => 1.0,
+        };
+        let position = Position {
+            x: physics.position.x + light.offset.x,
+            y: physics.position.y + light.offset.y,
+        };
+        add(position, light.radius, light.color, light.intensity * flicker);
+    }
+    lights
+}
+
+/// How bright the ambient light is, 0 to 1.
+pub fn brightness(color: Color) -> f32 {
+    (color.r + color.g + color.b) / 3.0
+}
@@ -33,6 +33,11 @@ impl Physics {
         }
     }
 
+    /// Y coordinate where the entity touches the floor, used to sort it when drawing.
+    pub fn foot_y(&self) -> f32 {
+        self.position.y + self.size.h_half()
+    }
+
     pub fn update_position(&mut self, ctx: &mut Context, direction: KeyCode, world_size: &Size) {
         let dt = ggez::timer::delta(ctx).as_secs_f32();
         match direction {
@@ -81,7 +86,7 @@ impl Position {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Size {
     pub width: f32,
     pub height: f32,
@@ -117,6 +122,16 @@ impl Direction {
             Direction::Left => 3,
         }
     }
+
+    /// Unit vector pointing this way, Y going down.
+    pub fn vector(self) -> (f32, f32) {
+        match self {
+            Direction::Up => (0.0, -1.0),
+            Direction::Down => (0.0, 1.0),
+            Direction::Left => (-1.0, 0.0),
+            Direction::Right => (1.0, 0.0),
+        }
+    }
 }
 
 fn clamp(value: &mut f32, low: f32, high: f32) {
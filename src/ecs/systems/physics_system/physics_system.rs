@@ -1,5 +1,9 @@
 use super::physics::*;
-use crate::ecs::{utils::constants::*};
+use crate::ecs::{
+    systems::console_system::console::{parse_arg, Console},
+    utils::constants::*,
+    utils::rng,
+};
 use ggez::{event::KeyCode, graphics, Context};
 use rand::Rng;
 
@@ -35,9 +39,7 @@ pub fn generate_npc_physics() -> Physics {
 }
 
 fn get_random_position() -> (f32, f32) {
-    let x = rand::thread_rng().gen_range(300..900);
-    let y = rand::thread_rng().gen_range(200..700);
-    (x as f32, y as f32)
+    rng::with_rng(|rng| (rng.gen_range(300..900) as f32, rng.gen_range(200..700) as f32))
 }
 
 // Update physics
@@ -60,12 +62,14 @@ pub fn update_player_physics(
 
         let mut player_collides: bool = false;
 
-        for (index, object_physics) in physics_components.iter().enumerate() {
+        for object_physics in physics_components.iter() {
             match object_physics {
+                // A box the player is already in doesn't stop it from walking out
                 Some(physics) => {
-                    if objects_collide(&new_potential_player_physics, &physics) {
+                    if objects_collide(&new_potential_player_physics, &physics)
+                        && !objects_collide(&new_player_physics, physics)
+                    {
                         player_collides = true;
-                        new_player_physics.current_focus = Some(index);
                     };
                 }
                 None => continue,
@@ -83,7 +87,6 @@ pub fn update_player_physics(
         };
 
         if !player_collides {
-            new_potential_player_physics.current_focus = None;
             new_player_physics = new_potential_player_physics;
         }
     }
@@ -91,7 +94,38 @@ pub fn update_player_physics(
     new_player_physics
 }
 
-fn objects_collide(a: &Physics, b: &Physics) -> bool {
+/// The entity the player would interact with. Of those `can_interact` accepts whose box is
+/// within `INTERACTION_RANGE` of the player's, the one most in front of the player wins, then
+/// the closest.
+pub fn find_focus(
+    player: &Physics,
+    physics_components: &[Option<Physics>],
+    can_interact: impl Fn(usize) -> bool,
+) -> Option<usize> {
+    let (facing_x, facing_y) = player.direction.map_or((0.0, 0.0), Direction::vector);
+    physics_components
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| can_interact(*index))
+        .filter_map(|(index, physics)| {
+            let physics = physics.as_ref()?;
+            let dx = physics.position.x - player.position.x;
+            let dy = physics.position.y - player.position.y;
+            let gap = (dx.abs() - player.size.w_half() - physics.size.w_half())
+                .max(dy.abs() - player.size.h_half() - physics.size.h_half())
+                .max(0.0);
+            if gap > INTERACTION_RANGE {
+                return None;
+            }
+            // 1 straight ahead down to -1 right behind
+            let facing = (dx * facing_x + dy * facing_y) / (dx * dx + dy * dy).sqrt().max(1.0);
+            Some((index, gap + (1.0 - facing) * INTERACTION_RANGE))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(index, _)| index)
+}
+
+pub fn objects_collide(a: &Physics, b: &Physics) -> bool {
 
     let collision = a.position.x - a.size.w_half() < b.position.x + b.size.w_half()
         && a.position.x + a.size.w_half() > b.position.x - b.size.w_half()
@@ -99,3 +133,29 @@ fn objects_collide(a: &Physics, b: &Physics) -> bool {
         && a.size.h_half() + a.position.y > b.position.y - b.size.h_half();
     return collision;
 }
+
+pub fn register_console_commands(console: &mut Console) {
+    console.register("tp", "<x> <y>", "moves the player to a world position", |_, _, game, args| {
+        let x = parse_arg(args, 0, "x")?;
+        let y = parse_arg(args, 1, "y")?;
+        let player = &mut game.player_physics;
+        player.position = Position { x, y };
+        player.position.clamp_self(
+            &player.size,
+            &Position {
+                x: game.world_size.width,
+                y: game.world_size.height,
+            },
+        );
+        Ok(format!("Player at {:.0}, {:.0}", player.position.x, player.position.y))
+    });
+    console.register("noclip", "", "lets the player walk through everything", |_, _, game, _| {
+        game.noclip = !game.noclip;
+        Ok(format!("noclip {}", if game.noclip { "on" } else { "off" }))
+    });
+    console.register("speed", "<speed>", "sets the player's speed in pixels per second", |_, _, game, args| {
+        let speed: f32 = parse_arg(args, 0, "speed")?;
+        game.player_physics.speed = speed.max(0.0);
+        Ok(format!("Player speed {}", game.player_physics.speed))
+    });
+}
@@ -1,12 +1,80 @@
-use ggez::{Context};
+use ggez::{graphics::Rect, Context};
+use rand::Rng;
 use super::super::physics_system::physics::{Physics, Position, Size, Direction};
-use super::super::super::utils::constants::{DEFAULT_CAMERA_W, DEFAULT_CAMERA_H, DEFAULT_CAMERA_SPEED, DEFAULT_CAMERA_OFFSET};
+use super::super::super::utils::constants::{
+    DEFAULT_CAMERA_W, DEFAULT_CAMERA_H, DEFAULT_CAMERA_OFFSET, DEFAULT_CAMERA_MODE,
+    DEFAULT_CAMERA_LOOK_AHEAD, MIN_CAMERA_ZOOM, MAX_CAMERA_ZOOM, CAMERA_LERP_STIFFNESS,
+    CAMERA_SPRING_STIFFNESS, CAMERA_SPRING_DAMPING,
+};
+
+/// How the camera follows the player.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CameraMode {
+    /// Always centered on the player.
+    HardLock,
+    /// Only moves when the player leaves the middle third of the screen, and then exactly
+    /// as much as needed to keep them at its edge.
+    DeadZone,
+    /// Closes a fraction of the distance to the player every second, higher is snappier.
+    Lerp { stiffness: f32 },
+    /// Damped spring pulling the camera towards the player.
+    Spring { stiffness: f32, damping: f32 },
+}
+
+impl CameraMode {
+    /// The mode called `name` in the console, with the default stiffness.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "hardlock" => Some(CameraMode::HardLock),
+            "deadzone" => Some(CameraMode::DeadZone),
+            "lerp" => Some(CameraMode::Lerp { stiffness: CAMERA_LERP_STIFFNESS }),
+            "spring" => Some(CameraMode::Spring {
+                stiffness: CAMERA_SPRING_STIFFNESS,
+                damping: CAMERA_SPRING_DAMPING,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CameraMode::HardLock => "hardlock",
+            CameraMode::DeadZone => "deadzone",
+            CameraMode::Lerp { .. } => "lerp",
+            CameraMode::Spring { .. } => "spring",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Shake {
+    intensity: f32,
+    duration: f32,
+    elapsed: f32,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Pan {
+    from: Position,
+    to: Position,
+    duration: f32,
+    elapsed: f32,
+}
 
 #[derive(Copy, Clone)]
 pub struct Camera {
+    /// Center of the view in world coordinates.
     pub position: Position,
+    /// Size of the view in screen pixels.
     pub size: Size,
-    pub speed: f32,
+    pub zoom: f32,
+    pub mode: CameraMode,
+    /// How far ahead of the player, in world units, the camera aims in the facing direction.
+    pub look_ahead: f32,
+    velocity: Position,
+    shake_offset: Position,
+    shake: Option<Shake>,
+    pan: Option<Pan>,
 }
 
 impl Camera {
@@ -17,70 +85,211 @@ impl Camera {
                 width: DEFAULT_CAMERA_W,
                 height: DEFAULT_CAMERA_H,
             },
-            speed: DEFAULT_CAMERA_SPEED,
+            zoom: 1.0,
+            mode: DEFAULT_CAMERA_MODE,
+            look_ahead: DEFAULT_CAMERA_LOOK_AHEAD,
+            velocity: Position { x: 0.0, y: 0.0 },
+            shake_offset: Position { x: 0.0, y: 0.0 },
+            shake: None,
+            pan: None,
         }
     }
 
     pub fn world_to_screen(&self, world_position: &Position) -> Position {
+        let center = self.view_center();
         Position {
-            x: world_position.x - (self.position.x - self.size.w_half()),
-            y: world_position.y - (self.position.y - self.size.h_half()),
+            x: (world_position.x - center.x) * self.zoom + self.size.w_half(),
+            y: (world_position.y - center.y) * self.zoom + self.size.h_half(),
         }
     }
 
-    pub fn update_position(&mut self, direction: Direction, ctx: &mut Context) {
-        let dt = ggez::timer::delta(ctx).as_secs_f32();
-        match direction {
-            Direction::Up => self.position.y -= self.speed * dt,
-            Direction::Down => self.position.y += self.speed * dt,
-            Direction::Left => self.position.x -= self.speed * dt,
-            Direction::Right => self.position.x += self.speed * dt
+    pub fn screen_to_world(&self, screen_position: &Position) -> Position {
+        let center = self.view_center();
+        Position {
+            x: (screen_position.x - self.size.w_half()) / self.zoom + center.x,
+            y: (screen_position.y - self.size.h_half()) / self.zoom + center.y,
         }
     }
 
-    pub fn is_player_approaching_camera_edge(
-        &self,
-        player_physics: &Physics
-    ) -> bool {
-        let player_camera_position = self.world_to_screen(&player_physics.position);
-        let camera_w_third = self.size.width / 3.0;
-        let camera_h_third = self.size.height / 3.0;
+    /// Size of the world area currently in view.
+    pub fn visible_size(&self) -> Size {
+        Size {
+            width: self.size.width / self.zoom,
+            height: self.size.height / self.zoom,
+        }
+    }
+
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom.clamp(MIN_CAMERA_ZOOM, MAX_CAMERA_ZOOM);
+    }
+
+    /// The middle third of the screen, in screen coordinates, used by `CameraMode::DeadZone`.
+    pub fn dead_zone(&self) -> Rect {
+        Rect::new(
+            self.size.width / 3.0,
+            self.size.height / 3.0,
+            self.size.width / 3.0,
+            self.size.height / 3.0,
+        )
+    }
+
+    /// Shakes the view for `duration` seconds, fading out from `intensity` pixels.
+    pub fn shake(&mut self, intensity: f32, duration: f32) {
+        self.shake = Some(Shake {
+            intensity,
+            duration,
+            elapsed: 0.0,
+        });
+    }
+
+    /// Moves the camera to `target` over `duration` seconds and holds it there, ignoring
+    /// the player, until `release` is called.
+    pub fn pan_to(&mut self, target: Position, duration: f32) {
+        self.pan = Some(Pan {
+            from: self.position,
+            to: target,
+            duration,
+            elapsed: 0.0,
+        });
+    }
+
+    pub fn release(&mut self) {
+        self.pan = None;
+    }
 
-        let left_boundry = camera_w_third;
-        let top_boundry = camera_h_third;
-        let bottom_boundry = self.size.height - camera_h_third;
-        let right_boundry = self.size.width - camera_h_third;
+    /// Puts the view on `position` right away, inside `world_size`, as when the player
+    /// enters another area.
+    pub fn jump_to(&mut self, position: Position, world_size: &Size) {
+        self.position = position;
+        self.velocity = Position { x: 0.0, y: 0.0 };
+        self.pan = None;
+        self.clamp_to_world(world_size);
+    }
 
-        return (player_camera_position.x < left_boundry && player_physics.direction == Some(Direction::Left))
-            || (player_camera_position.y < top_boundry && player_physics.direction == Some(Direction::Up))
-            || (player_camera_position.x > right_boundry && player_physics.direction == Some(Direction::Right))
-            || (player_camera_position.y > bottom_boundry && player_physics.direction == Some(Direction::Down));
+    pub fn is_panning(&self) -> bool {
+        self.pan.is_some()
     }
 
-    pub fn is_within_world_bounds(&self, world_size: &Size, direction: Direction) -> bool {
-        let camera_w_half = self.size.w_half();
-        let camera_h_half = self.size.h_half();
+    pub fn update(&mut self, ctx: &mut Context, player_physics: &Physics, world_size: &Size) {
+        let dt = ggez::timer::delta(ctx).as_secs_f32();
+
+        match self.pan.as_mut() {
+            Some(pan) => {
+                pan.elapsed = (pan.elapsed + dt).min(pan.duration);
+                let t = if pan.duration > 0.0 {
+                    smoothstep(pan.elapsed / pan.duration)
+                } else {
+                    1.0
+                };
+                self.position = Position {
+                    x: pan.from.x + (pan.to.x - pan.from.x) * t,
+                    y: pan.from.y + (pan.to.y - pan.from.y) * t,
+                };
+            }
+            None => self.follow(player_physics, dt),
+        }
+
+        self.clamp_to_world(world_size);
+        self.update_shake(dt);
+    }
+
+    fn follow(&mut self, player_physics: &Physics, dt: f32) {
+        let target = self.follow_target(player_physics);
+
+        match self.mode {
+            CameraMode::HardLock => self.position = target,
+            CameraMode::DeadZone => {
+                let zone_w_half = self.visible_size().width / 6.0;
+                let zone_h_half = self.visible_size().height / 6.0;
+                self.position.x = push_into_zone(self.position.x, target.x, zone_w_half);
+                self.position.y = push_into_zone(self.position.y, target.y, zone_h_half);
+            }
+            CameraMode::Lerp { stiffness } => {
+                let t = 1.0 - (-stiffness * dt).exp();
+                self.position.x += (target.x - self.position.x) * t;
+                self.position.y += (target.y - self.position.y) * t;
+            }
+            CameraMode::Spring { stiffness, damping } => {
+                let acceleration = Position {
+                    x: stiffness * (target.x - self.position.x) - damping * self.velocity.x,
+                    y: stiffness * (target.y - self.position.y) - damping * self.velocity.y,
+                };
+                self.velocity.x += acceleration.x * dt;
+                self.velocity.y += acceleration.y * dt;
+                self.position.x += self.velocity.x * dt;
+                self.position.y += self.velocity.y * dt;
+            }
+        }
+    }
+
+    fn follow_target(&self, player_physics: &Physics) -> Position {
+        let (dx, dy) = match player_physics.direction {
+            Some(Direction::Up) => (0.0, -1.0),
+            Some(Direction::Down) => (0.0, 1.0),
+            Some(Direction::Left) => (-1.0, 0.0),
+            Some(Direction::Right) => (1.0, 0.0),
+            None => (0.0, 0.0),
+        };
+        Position {
+            x: player_physics.position.x + dx * self.look_ahead,
+            y: player_physics.position.y + dy * self.look_ahead,
+        }
+    }
 
-        let left_boundry = self.position.x - camera_w_half <= -DEFAULT_CAMERA_OFFSET;
-        let top_boundry = self.position.y - camera_h_half <= -DEFAULT_CAMERA_OFFSET;
+    /// Keeps the view inside the world, centering it on an axis where the world is smaller
+    /// than the view.
+    fn clamp_to_world(&mut self, world_size: &Size) {
+        let visible = self.visible_size();
+        self.position.x = clamp_axis(self.position.x, visible.w_half(), world_size.width);
+        self.position.y = clamp_axis(self.position.y, visible.h_half(), world_size.height);
+    }
 
-        let right_boundry =
-            self.position.x + camera_w_half >= world_size.width + DEFAULT_CAMERA_OFFSET;
-        let bottom_boundry =
-            self.position.y + camera_h_half >= world_size.height + DEFAULT_CAMERA_OFFSET;
+    fn update_shake(&mut self, dt: f32) {
+        self.shake_offset = Position { x: 0.0, y: 0.0 };
 
-        !((left_boundry && direction == Direction::Left)
-            || (top_boundry && direction == Direction::Up)
-            || (bottom_boundry && direction == Direction::Down)
-            || (right_boundry && direction == Direction::Right))
+        if let Some(shake) = self.shake.as_mut() {
+            shake.elapsed += dt;
+            if shake.elapsed >= shake.duration {
+                self.shake = None;
+            } else {
+                let strength = shake.intensity * (1.0 - shake.elapsed / shake.duration);
+                let mut rng = rand::thread_rng();
+                self.shake_offset = Position {
+                    x: rng.gen_range(-1.0..=1.0) * strength / self.zoom,
+                    y: rng.gen_range(-1.0..=1.0) * strength / self.zoom,
+                };
+            }
+        }
     }
 
-    pub fn maybe_update(&mut self, ctx: &mut Context, player_physics: &Physics, world_size: &Size) {
-        let should_update_camera = self.is_player_approaching_camera_edge(player_physics)
-            && self.is_within_world_bounds(world_size, player_physics.direction.unwrap());
-    
-        if should_update_camera {
-            self.update_position(player_physics.direction.unwrap(), ctx);
+    fn view_center(&self) -> Position {
+        Position {
+            x: self.position.x + self.shake_offset.x,
+            y: self.position.y + self.shake_offset.y,
         }
     }
 }
+
+fn push_into_zone(camera: f32, target: f32, zone_half: f32) -> f32 {
+    if target > camera + zone_half {
+        target - zone_half
+    } else if target < camera - zone_half {
+        target + zone_half
+    } else {
+        camera
+    }
+}
+
+fn clamp_axis(camera: f32, visible_half: f32, world_length: f32) -> f32 {
+    let low = visible_half - DEFAULT_CAMERA_OFFSET;
+    let high = world_length + DEFAULT_CAMERA_OFFSET - visible_half;
+    if low > high {
+        world_length / 2.0
+    } else {
+        camera.clamp(low, high)
+    }
+}
+
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
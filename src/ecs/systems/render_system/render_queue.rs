@@ -0,0 +1,101 @@
+use ggez::{
+    graphics::{self, spritebatch::SpriteBatch, Color, DrawParam},
+    Context, GameResult,
+};
+use std::cmp::Ordering;
+
+use crate::ecs::{
+    assets::asset_manager::{AssetManager, ImageHandle},
+    atlas,
+};
+
+/// Coarse draw order of the world. Items are sorted by layer first and by their foot Y
+/// position within a layer, so whatever stands lower on screen covers what is behind it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RenderLayer {
+    Floor,
+    Entities,
+    Overhead,
+}
+
+struct RenderItem {
+    layer: RenderLayer,
+    sort_y: f32,
+    image: ImageHandle,
+    param: DrawParam,
+}
+
+pub struct RenderQueue {
+    items: Vec<RenderItem>,
+    batch: Option<SpriteBatch>,
+    /// Color everything submitted is tinted with, see `set_tint`.
+    tint: Option<Color>,
+}
+
+impl RenderQueue {
+    pub fn new() -> Self {
+        RenderQueue {
+            items: Vec::new(),
+            batch: None,
+            tint: None,
+        }
+    }
+
+    pub fn submit(&mut self, layer: RenderLayer, sort_y: f32, image: ImageHandle, param: DrawParam) {
+        let param = match self.tint {
+            Some(tint) => param.color(tint),
+            None => param,
+        };
+        self.items.push(RenderItem {
+            layer,
+            sort_y,
+            image,
+            param,
+        });
+    }
+
+    /// Tints what's submitted from now on, e.g. to highlight an entity, until set back to none.
+    pub fn set_tint(&mut self, tint: Option<Color>) {
+        self.tint = tint;
+    }
+
+    /// Sorts everything submitted this frame and draws it, one batch per run of sprites
+    /// sharing the same image.
+    pub fn flush(&mut self, ctx: &mut Context, asset_manager: &AssetManager) -> GameResult {
+        self.items.sort_by(|a, b| {
+            a.layer
+                .cmp(&b.layer)
+                .then(a.sort_y.partial_cmp(&b.sort_y).unwrap_or(Ordering::Equal))
+        });
+
+        let mut start = 0;
+        while start < self.items.len() {
+            let image = self.items[start].image;
+            let end = self.items[start..]
+                .iter()
+                .position(|item| item.image != image)
+                .map_or(self.items.len(), |offset| start + offset);
+
+            let batch = self
+                .batch
+                .get_or_insert_with(|| atlas::create_batch_sprite(asset_manager.image(image).clone()));
+            batch.set_image(asset_manager.image(image).clone());
+            for item in &self.items[start..end] {
+                batch.add(item.param);
+            }
+            graphics::draw(ctx, batch, DrawParam::default())?;
+            batch.clear();
+
+            start = end;
+        }
+
+        self.items.clear();
+        Ok(())
+    }
+}
+
+impl Default for RenderQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,103 @@
+use ggez::{
+    conf::{FullscreenType, NumSamples},
+    graphics::{self, Canvas, Color, DrawParam, FilterMode, Rect},
+    Context, GameResult,
+};
+
+use super::super::physics_system::physics::{Position, Size};
+
+/// Renders the game at a fixed virtual resolution and scales it to the window, by whole
+/// multiples when it fits so pixel art stays crisp, with black bars on the leftover space.
+pub struct Viewport {
+    pub virtual_size: Size,
+    pub window_size: Size,
+    pub scale: f32,
+    /// Top-left corner of the scaled picture inside the window.
+    pub offset: Position,
+    pub fullscreen: bool,
+    /// Scale by whole multiples when the window fits them, otherwise fill the window.
+    pub integer_scaling: bool,
+    canvas: Canvas,
+}
+
+impl Viewport {
+    pub fn new(ctx: &mut Context, virtual_size: Size) -> GameResult<Self> {
+        let mut canvas = Canvas::new(
+            ctx,
+            virtual_size.width as u16,
+            virtual_size.height as u16,
+            NumSamples::One,
+            graphics::get_window_color_format(ctx),
+        )?;
+        canvas.set_filter(FilterMode::Nearest);
+
+        let (width, height) = graphics::drawable_size(ctx);
+        let mut viewport = Viewport {
+            virtual_size,
+            window_size: Size { width, height },
+            scale: 1.0,
+            offset: Position { x: 0.0, y: 0.0 },
+            fullscreen: false,
+            integer_scaling: true,
+            canvas,
+        };
+        viewport.resize(ctx, width, height)?;
+        Ok(viewport)
+    }
+
+    pub fn resize(&mut self, ctx: &mut Context, width: f32, height: f32) -> GameResult {
+        self.window_size = Size { width, height };
+
+        let fit = (width / self.virtual_size.width).min(height / self.virtual_size.height);
+        self.scale = if self.integer_scaling && fit >= 1.0 { fit.floor() } else { fit };
+
+        self.offset = Position {
+            x: ((width - self.virtual_size.width * self.scale) / 2.0).floor(),
+            y: ((height - self.virtual_size.height * self.scale) / 2.0).floor(),
+        };
+
+        graphics::set_screen_coordinates(ctx, Rect::new(0.0, 0.0, width, height))
+    }
+
+    pub fn toggle_fullscreen(&mut self, ctx: &mut Context) -> GameResult {
+        self.fullscreen = !self.fullscreen;
+        let fullscreen_type = if self.fullscreen {
+            FullscreenType::Desktop
+        } else {
+            FullscreenType::Windowed
+        };
+        graphics::set_fullscreen(ctx, fullscreen_type)
+    }
+
+    /// Starts drawing into the virtual resolution target.
+    pub fn begin(&self, ctx: &mut Context) -> GameResult {
+        graphics::set_canvas(ctx, Some(&self.canvas));
+        graphics::set_screen_coordinates(
+            ctx,
+            Rect::new(0.0, 0.0, self.virtual_size.width, self.virtual_size.height),
+        )
+    }
+
+    /// Scales what was drawn since `begin` onto the window.
+    pub fn finish(&self, ctx: &mut Context) -> GameResult {
+        graphics::set_canvas(ctx, None);
+        graphics::set_screen_coordinates(
+            ctx,
+            Rect::new(0.0, 0.0, self.window_size.width, self.window_size.height),
+        )?;
+        graphics::clear(ctx, Color::BLACK);
+
+        let params = DrawParam::default()
+            .dest([self.offset.x, self.offset.y])
+            .scale([self.scale, self.scale]);
+        graphics::draw(ctx, &self.canvas, params)
+    }
+
+    /// Converts window pixels, e.g. the mouse position, into virtual resolution pixels.
+    pub fn window_to_virtual(&self, window_position: &Position) -> Position {
+        Position {
+            x: (window_position.x - self.offset.x) / self.scale,
+            y: (window_position.y - self.offset.y) / self.scale,
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::ecs::utils::constants::{CLOCK_START, CLOCK_TIME_SCALE, MINUTES_PER_DAY};
+
+/// How the clock runs, from `clock.json`.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ClockConfig {
+    /// Time of day the office opens at, `HH:MM`.
+    #[serde(default = "default_start")]
+    pub start: String,
+    /// In-game minutes that go by every real second.
+    #[serde(default = "default_time_scale")]
+    pub minutes_per_second: f32,
+}
+
+fn default_start() -> String {
+    CLOCK_START.to_string()
+}
+
+fn default_time_scale() -> f32 {
+    CLOCK_TIME_SCALE
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        ClockConfig {
+            start: default_start(),
+            minutes_per_second: default_time_scale(),
+        }
+    }
+}
+
+/// The time of day in the office, which NPC schedules follow.
+pub struct Clock {
+    pub day: u32,
+    /// Minutes since midnight.
+    pub minute: f32,
+    pub minutes_per_second: f32,
+}
+
+impl Clock {
+    pub fn new(config: &ClockConfig) -> Self {
+        Clock {
+            day: 1,
+            minute: parse_clock_time(&config.start).unwrap_or(0.0),
+            minutes_per_second: config.minutes_per_second.max(0.0),
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.minute += dt * self.minutes_per_second;
+        while self.minute >= MINUTES_PER_DAY {
+            self.minute -= MINUTES_PER_DAY;
+            self.day += 1;
+        }
+    }
+
+    pub fn set_time(&mut self, minute: f32) {
+        self.minute = minute.rem_euclid(MINUTES_PER_DAY);
+    }
+
+    /// E.g. `09:05`.
+    pub fn time_text(&self) -> String {
+        format_clock_time(self.minute)
+    }
+}
+
+/// Minutes since midnight of a `HH:MM` time.
+pub fn parse_clock_time(text: &str) -> Option<f32> {
+    let (hours, minutes) = text.trim().split_once(':')?;
+    let hours: u32 = hours.parse().ok()?;
+    let minutes: u32 = minutes.parse().ok()?;
+    if hours >= 24 || minutes >= 60 {
+        return None;
+    }
+    Some((hours * 60 + minutes) as f32)
+}
+
+pub fn format_clock_time(minute: f32) -> String {
+    let minute = minute as u32;
+    format!("{:02}:{:02}", minute / 60 % 24, minute % 60)
+}
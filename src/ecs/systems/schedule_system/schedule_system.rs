@@ -0,0 +1,208 @@
+use ggez::{
+    graphics::{self, Color, DrawMode, DrawParam, MeshBuilder, Rect},
+    Context, GameResult,
+};
+
+use super::clock::{format_clock_time, parse_clock_time, Clock};
+use crate::ecs::{
+    components::schedule::{Schedule, ScheduleEntry, SchedulePlace},
+    scenes::shared_state::SharedState,
+    systems::{
+        console_system::console::{parse_arg, Console},
+        input_system::interaction::Interaction,
+        physics_system::{
+            physics::{Direction, Physics, Position},
+            physics_system::objects_collide,
+        },
+    },
+    utils::constants::NPC_WALK_SPEED,
+};
+
+const CLOCK_BACKGROUND: Color = Color::new(0.0, 0.0, 0.0, 0.6);
+
+pub fn register_console_commands(console: &mut Console) {
+    console.register("time", "[HH:MM]", "prints the time of day, or jumps to it", |_, shared, game, args| {
+        if let Some(time) = args.first() {
+            let minute = parse_clock_time(time).ok_or_else(|| format!("expected HH:MM, got {}", time))?;
+            game.clock.set_time(minute);
+            // Everyone goes straight to where they should be by then
+            reset_schedules(&mut game.schedule_components);
+        }
+        let day = shared.localization.tr("clock.day");
+        Ok(format!("{} {} {}", day, game.clock.day, game.clock.time_text()))
+    });
+    console.register("timescale", "<minutes>", "sets the in-game minutes per second, 0 stops the clock", |_, _, game, args| {
+        let minutes: f32 = parse_arg(args, 0, "minutes")?;
+        game.clock.minutes_per_second = minutes.max(0.0);
+        Ok(format!("{} minutes per second", game.clock.minutes_per_second))
+    });
+}
+
+/// Index of the entry in effect at `minute`. Before the first entry of the day it's still
+/// the last one of the day before.
+pub fn active_entry(entries: &[ScheduleEntry], minute: f32) -> Option<usize> {
+    entries
+        .iter()
+        .rposition(|entry| entry.minute <= minute)
+        .or_else(|| entries.len().checked_sub(1))
+}
+
+/// Moves scheduled NPCs along their day. When an entry starts they get its interaction and
+/// walk to its place, or to `entrance` and out of the office when it sends them away,
+/// coming back in through it later. A schedule that hasn't run yet puts the NPC straight
+/// where it should be.
+pub fn update_schedules(
+    clock: &Clock,
+    dt: f32,
+    entrance: Option<Position>,
+    player: &Physics,
+    schedules: &mut [Option<Schedule>],
+    physics_components: &mut [Option<Physics>],
+    interactions: &mut [Option<Interaction>],
+) {
+    for (index, schedule) in schedules.iter_mut().enumerate() {
+        let schedule = match schedule {
+            Some(schedule) => schedule,
+            None => continue,
+        };
+        let physics = &mut physics_components[index];
+
+        let active = active_entry(&schedule.entries, clock.minute);
+        if let Some(entry_index) = active.filter(|_| active != schedule.current) {
+            let first_run = schedule.current.is_none();
+            schedule.current = active;
+            let entry = &schedule.entries[entry_index];
+            let place = entry.place;
+            interactions[index] = entry
+                .interaction
+                .clone()
+                .or_else(|| schedule.main_interaction.clone());
+            start_entry(schedule, place, physics, entrance, first_run);
+        }
+        walk(schedule, physics, player, dt);
+    }
+}
+
+/// Makes every schedule start over from the current time, see `update_schedules`.
+pub fn reset_schedules(schedules: &mut [Option<Schedule>]) {
+    for schedule in schedules.iter_mut().flatten() {
+        schedule.current = None;
+        schedule.target = None;
+        schedule.leaving = false;
+    }
+}
+
+fn start_entry(
+    schedule: &mut Schedule,
+    place: SchedulePlace,
+    physics: &mut Option<Physics>,
+    entrance: Option<Position>,
+    teleport: bool,
+) {
+    match place {
+        SchedulePlace::Stay => {}
+        SchedulePlace::At(position) => {
+            schedule.leaving = false;
+            // Coming back in
+            if let Some(mut away) = schedule.away.take() {
+                away.position = match entrance {
+                    Some(entrance) if !teleport => entrance,
+                    _ => position,
+                };
+                *physics = Some(away);
+            }
+            match physics {
+                Some(physics) if teleport => {
+                    physics.position = position;
+                    schedule.target = None;
+                }
+                _ => schedule.target = Some(position),
+            }
+        }
+        // An NPC taken out of the office some other way stays out
+        SchedulePlace::Away if physics.is_none() => {}
+        SchedulePlace::Away => match entrance {
+            Some(entrance) if !teleport => {
+                schedule.target = Some(entrance);
+                schedule.leaving = true;
+            }
+            _ => {
+                schedule.away = physics.take();
+                schedule.target = None;
+                schedule.leaving = false;
+            }
+        },
+    }
+}
+
+/// A step in a straight line towards the target, there's no path finding around desks. The
+/// NPC waits while the step would walk it into the player.
+fn walk(schedule: &mut Schedule, physics_slot: &mut Option<Physics>, player: &Physics, dt: f32) {
+    let (target, physics) = match (schedule.target, physics_slot.as_mut()) {
+        (Some(target), Some(physics)) => (target, physics),
+        _ => return,
+    };
+
+    let dx = target.x - physics.position.x;
+    let dy = target.y - physics.position.y;
+    let distance = (dx * dx + dy * dy).sqrt();
+    let step = NPC_WALK_SPEED * dt;
+    let mut next = *physics;
+    next.position = if distance <= step {
+        target
+    } else {
+        Position {
+            x: physics.position.x + dx / distance * step,
+            y: physics.position.y + dy / distance * step,
+        }
+    };
+    // One already overlapping the player keeps going, so it doesn't stay stuck there
+    if objects_collide(&next, player) && !objects_collide(physics, player) {
+        physics.walking = false;
+        return;
+    }
+
+    if distance <= step {
+        physics.position = target;
+        physics.walking = false;
+        schedule.target = None;
+        if schedule.leaving {
+            schedule.leaving = false;
+            schedule.away = physics_slot.take();
+        }
+        return;
+    }
+
+    physics.position = next.position;
+    physics.walking = true;
+    physics.direction = Some(if dx.abs() > dy.abs() {
+        if dx > 0.0 { Direction::Right } else { Direction::Left }
+    } else if dy > 0.0 {
+        Direction::Down
+    } else {
+        Direction::Up
+    });
+}
+
+/// The day and time in the top right corner.
+pub fn draw_clock(ctx: &mut Context, shared: &SharedState, clock: &Clock) -> GameResult {
+    let font = shared.asset_manager.ui_font();
+    let day = shared.localization.tr("clock.day");
+    let text = format!("{} {}  {}", day, clock.day, format_clock_time(clock.minute));
+    let padding = 6.0;
+    let width = font.text_width(ctx, &text) + 2.0 * padding;
+    let height = font.line_height(ctx) + 2.0 * padding;
+    let rect = Rect::new(shared.viewport.virtual_size.width - width - 8.0, 8.0, width, height);
+
+    let mesh = MeshBuilder::new()
+        .rectangle(DrawMode::fill(), rect, CLOCK_BACKGROUND)?
+        .build(ctx)?;
+    graphics::draw(ctx, &mesh, DrawParam::default())?;
+    font.draw_text(
+        ctx,
+        &shared.asset_manager,
+        &text,
+        [rect.x + padding, rect.y + padding],
+        Color::WHITE,
+    )
+}
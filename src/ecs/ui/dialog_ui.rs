@@ -0,0 +1,185 @@
+use ggez::{
+    graphics::{Color, Rect},
+    Context, GameResult,
+};
+
+use super::{
+    layout::Anchor,
+    widget::{Label, List, NineSlice, Node, Widget},
+};
+use crate::ecs::{
+    assets::asset_manager::{AssetManager, ImageHandle},
+    components::npc::Npc,
+    scenes::shared_state::UiImages,
+    systems::{input_system::interaction::Interaction, physics_system::physics::Size},
+    utils::{constants::DEFAULT_TEXT_SPEED, localization::Localization},
+};
+
+const DIALOG_BOX_W: f32 = 612.0;
+const DIALOG_BOX_H: f32 = 162.0;
+const AVATAR_BOX_W: f32 = 178.0;
+const AVATAR_BOX_H: f32 = 154.0;
+const NINE_SLICE_BORDER: f32 = 12.0;
+const TEXT_H_WITH_OPTIONS: f32 = 40.0;
+
+/// Who's talking in the dialog box, an NPC or an object.
+#[derive(Clone, Debug)]
+pub struct Speaker {
+    pub name: String,
+    pub avatar: Option<ImageHandle>,
+    /// Pitch of the dialogue blips, objects make none.
+    pub voice_pitch: Option<f32>,
+}
+
+impl Speaker {
+    pub fn npc(npc: &Npc) -> Self {
+        Speaker {
+            name: npc.name.clone(),
+            avatar: Some(npc.avatar),
+            voice_pitch: Some(npc.voice_pitch),
+        }
+    }
+
+    pub fn object(name: &str) -> Self {
+        Speaker {
+            name: name.to_string(),
+            avatar: None,
+            voice_pitch: None,
+        }
+    }
+}
+
+/// The conversation box: avatar and name on the left, what the speaker says and the player's
+/// options on the right.
+pub struct DialogUi {
+    root: Node,
+}
+
+impl DialogUi {
+    pub fn new(ui_images: &UiImages) -> Self {
+        let speech = Node::new("dialog.speech", Widget::Panel(None))
+            .anchor(Anchor::TopLeft)
+            .offset(AVATAR_BOX_W, 0.0)
+            .size(DIALOG_BOX_W - AVATAR_BOX_W, DIALOG_BOX_H)
+            .child(
+                Node::new(
+                    "dialog.text",
+                    Widget::Label(Label::new("", Color::WHITE).typewriter(DEFAULT_TEXT_SPEED)),
+                )
+                    .anchor(Anchor::TopLeft)
+                    .offset(10.0, 14.0)
+                    .size(DIALOG_BOX_W - AVATAR_BOX_W - 20.0, TEXT_H_WITH_OPTIONS),
+            )
+            .child(
+                Node::new("dialog.separator", Widget::Separator(Color::from_rgb(210, 218, 226)))
+                    .anchor(Anchor::TopLeft)
+                    .offset(10.0, 54.0)
+                    .size(150.0, 2.0),
+            )
+            .child(
+                Node::new("dialog.options", Widget::List(List::new(Vec::new())))
+                    .anchor(Anchor::TopLeft)
+                    .offset(10.0, 62.0)
+                    .size(DIALOG_BOX_W - AVATAR_BOX_W - 20.0, 90.0),
+            );
+
+        let avatar_box = Node::new(
+            "dialog.avatar_box",
+            Widget::Panel(Some(NineSlice {
+                image: ui_images.avatar_box,
+                border: NINE_SLICE_BORDER,
+            })),
+        )
+        .anchor(Anchor::TopLeft)
+        .size(AVATAR_BOX_W, AVATAR_BOX_H)
+        .child(
+            Node::new("dialog.avatar", Widget::Image(None))
+                .anchor(Anchor::TopCenter)
+                .offset(0.0, -19.0)
+                .size(128.0, 128.0),
+        )
+        .child(
+            Node::new("dialog.name", Widget::Label(Label::new("", Color::WHITE).centered()))
+                .anchor(Anchor::BottomCenter)
+                .offset(0.0, -12.0)
+                .size(AVATAR_BOX_W - 20.0, 20.0),
+        );
+
+        let dialog_box = Node::new(
+            "dialog.box",
+            Widget::Panel(Some(NineSlice {
+                image: ui_images.dialog_box,
+                border: NINE_SLICE_BORDER,
+            })),
+        )
+        .anchor(Anchor::BottomCenter)
+        .offset(0.0, -19.0)
+        .size(DIALOG_BOX_W, DIALOG_BOX_H)
+        .child(avatar_box)
+        .child(speech);
+
+        DialogUi {
+            root: Node::new("dialog", Widget::Panel(None)).child(dialog_box),
+        }
+    }
+
+    /// Updates the retained nodes to show `interaction` as said by `speaker`.
+    pub fn sync(&mut self, speaker: &Speaker, interaction: &Interaction, localization: &Localization) {
+        self.root.set_image("dialog.avatar", speaker.avatar);
+        if let Some(name) = self.root.label_mut("dialog.name") {
+            name.set_text(&speaker.name);
+        }
+        if let Some(text) = self.root.label_mut("dialog.text") {
+            text.set_text(localization.tr(&interaction.dialog));
+        }
+
+        let has_options = interaction.options.is_some();
+        let text_finished = self.text_finished();
+        if let Some(options) = self.root.list_mut("dialog.options") {
+            options.items = interaction
+                .options
+                .iter()
+                .flatten()
+                .map(|option| localization.tr(option).to_string())
+                .collect();
+            options.selected = interaction.hovered_option;
+        }
+        self.root.set_visible("dialog.separator", has_options);
+        self.root.set_visible("dialog.options", has_options && text_finished);
+        if let Some(text_node) = self.root.find_mut("dialog.text") {
+            text_node.size.height = if has_options {
+                TEXT_H_WITH_OPTIONS
+            } else {
+                DIALOG_BOX_H - 28.0
+            };
+        }
+    }
+
+    /// Moves the typewriter forward, returning how many characters appeared.
+    pub fn update(&mut self, dt: f32) -> usize {
+        self.root.label_mut("dialog.text").map_or(0, |text| text.update(dt))
+    }
+
+    /// Completes or turns the current page. Returns false once the whole text is shown,
+    /// so the key can go on to pick an option or close the dialog.
+    pub fn advance(&mut self) -> bool {
+        self.root.label_mut("dialog.text").is_some_and(|text| text.advance())
+    }
+
+    /// Starts revealing the text from the beginning, for a conversation that just began.
+    pub fn restart(&mut self) {
+        if let Some(text) = self.root.label_mut("dialog.text") {
+            text.restart();
+        }
+    }
+
+    /// Whether the whole text is shown, and with it the options.
+    pub fn text_finished(&mut self) -> bool {
+        self.root.label_mut("dialog.text").is_none_or(|text| text.is_finished())
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context, asset_manager: &AssetManager, screen: &Size) -> GameResult {
+        let screen_rect = Rect::new(0.0, 0.0, screen.width, screen.height);
+        self.root.draw(ctx, asset_manager, screen_rect)
+    }
+}
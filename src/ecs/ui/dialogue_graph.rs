@@ -0,0 +1,183 @@
+use ggez::{
+    graphics::{self, Color, DrawMode, DrawParam, MeshBuilder, Rect},
+    Context, GameResult,
+};
+
+use super::markup::{parse_markup, plain_text};
+use crate::ecs::{
+    assets::asset_manager::AssetManager,
+    systems::{input_system::interaction::Interaction, physics_system::physics::Position},
+    utils::localization::Localization,
+};
+
+const COLUMN_W: f32 = 250.0;
+const ROW_H: f32 = 70.0;
+const NODE_W: f32 = 220.0;
+const NODE_H: f32 = 56.0;
+/// Characters of a node's text shown before it's cut short.
+const NODE_TEXT_CHARS: usize = 26;
+
+const NODE_COLOR: Color = Color::new(0.15, 0.2, 0.3, 1.0);
+const SELECTED_COLOR: Color = Color::new(1.0, 1.0, 0.0, 1.0);
+const EDGE_COLOR: Color = Color::new(0.7, 0.7, 0.7, 1.0);
+const PROBLEM_COLOR: Color = Color::new(1.0, 0.3, 0.3, 1.0);
+const OPTION_COLOR: Color = Color::new(0.6, 0.8, 1.0, 1.0);
+
+/// One interaction of a dialogue tree, placed on the graph.
+pub struct GraphNode {
+    /// Options picked to get here from the root, see `Interaction::at_path`.
+    pub path: Vec<usize>,
+    /// The option of the parent that leads here, none for the root or a missing option.
+    pub option: Option<String>,
+    pub dialog: String,
+    pub has_problems: bool,
+    /// In graph coordinates, before scrolling.
+    pub rect: Rect,
+}
+
+/// Lays the tree out left to right, a column per depth and a row per leaf, with every
+/// parent level with its first child.
+pub fn layout_graph(root: &Interaction) -> Vec<GraphNode> {
+    let mut nodes = Vec::new();
+    let mut next_row = 0;
+    place_node(root, None, Vec::new(), &mut next_row, &mut nodes);
+    nodes
+}
+
+fn place_node(
+    interaction: &Interaction,
+    option: Option<String>,
+    path: Vec<usize>,
+    next_row: &mut usize,
+    nodes: &mut Vec<GraphNode>,
+) {
+    let first_row = *next_row;
+    let sub_interactions = interaction.sub_interactions.iter().flatten();
+    for (index, sub_interaction) in sub_interactions.enumerate() {
+        let mut sub_path = path.clone();
+        sub_path.push(index);
+        let sub_option = interaction
+            .options
+            .as_ref()
+            .and_then(|options| options.get(index).cloned());
+        place_node(sub_interaction, sub_option, sub_path, next_row, nodes);
+    }
+    if *next_row == first_row {
+        *next_row += 1;
+    }
+
+    let depth = path.len();
+    nodes.push(GraphNode {
+        path,
+        option,
+        dialog: interaction.dialog.clone(),
+        has_problems: !interaction.own_problems("").is_empty(),
+        rect: Rect::new(depth as f32 * COLUMN_W, first_row as f32 * ROW_H, NODE_W, NODE_H),
+    });
+}
+
+/// How far to scroll the graph so the selected node shows in `area`.
+pub fn scroll_to(nodes: &[GraphNode], selected: &[usize], area: Rect) -> Position {
+    match nodes.iter().find(|node| node.path == selected) {
+        Some(node) => Position {
+            x: (node.rect.right() + COLUMN_W - NODE_W - area.w).max(0.0),
+            y: (node.rect.bottom() + ROW_H - NODE_H - area.h).max(0.0),
+        },
+        None => Position { x: 0.0, y: 0.0 },
+    }
+}
+
+/// Draws the nodes into `area`, each with the option leading to it over its dialog, both
+/// translated. Nodes with problems are outlined in red.
+pub fn draw_graph(
+    ctx: &mut Context,
+    asset_manager: &AssetManager,
+    localization: &Localization,
+    nodes: &[GraphNode],
+    selected: &[usize],
+    area: Rect,
+) -> GameResult {
+    let scroll = scroll_to(nodes, selected, area);
+    let to_area = |rect: Rect| Rect::new(area.x + rect.x - scroll.x, area.y + rect.y - scroll.y, rect.w, rect.h);
+    let visible = |rect: &Rect| rect.overlaps(&area);
+
+    let mut mesh = MeshBuilder::new();
+    // Something to build even when every node is scrolled away
+    mesh.rectangle(DrawMode::stroke(1.0), area, EDGE_COLOR)?;
+    for node in nodes.iter() {
+        let rect = to_area(node.rect);
+        if let Some(parent) = node
+            .path
+            .split_last()
+            .and_then(|(_, parent_path)| nodes.iter().find(|parent| parent.path == parent_path))
+        {
+            let parent_rect = to_area(parent.rect);
+            mesh.line(
+                &[
+                    [parent_rect.right(), parent_rect.y + NODE_H / 2.0],
+                    [rect.x, rect.y + NODE_H / 2.0],
+                ],
+                1.0,
+                EDGE_COLOR,
+            )?;
+        }
+        if !visible(&rect) {
+            continue;
+        }
+        mesh.rectangle(DrawMode::fill(), rect, NODE_COLOR)?;
+        let outline = if node.path == selected {
+            SELECTED_COLOR
+        } else if node.has_problems {
+            PROBLEM_COLOR
+        } else {
+            EDGE_COLOR
+        };
+        let width = if node.path == selected || node.has_problems { 2.0 } else { 1.0 };
+        mesh.rectangle(DrawMode::stroke(width), rect, outline)?;
+    }
+    let mesh = mesh.build(ctx)?;
+    graphics::draw(ctx, &mesh, DrawParam::default())?;
+
+    let font = asset_manager.ui_font();
+    let line_height = font.line_height(ctx);
+    for node in nodes.iter() {
+        let rect = to_area(node.rect);
+        if !visible(&rect) {
+            continue;
+        }
+        let option = match (&node.option, node.path.is_empty()) {
+            (_, true) => "(start)".to_string(),
+            (Some(option), false) => format!("> {}", translated(localization, option)),
+            (None, false) => "> (missing option)".to_string(),
+        };
+        let option_color = if node.option.is_none() && !node.path.is_empty() {
+            PROBLEM_COLOR
+        } else {
+            OPTION_COLOR
+        };
+        font.draw_text(ctx, asset_manager, &shorten(&option), [rect.x + 6.0, rect.y + 6.0], option_color)?;
+        font.draw_text(
+            ctx,
+            asset_manager,
+            &shorten(&translated(localization, &node.dialog)),
+            [rect.x + 6.0, rect.y + 6.0 + line_height],
+            Color::WHITE,
+        )?;
+    }
+    Ok(())
+}
+
+/// The text shown for a key, without its markup.
+fn translated(localization: &Localization, key: &str) -> String {
+    plain_text(&parse_markup(localization.tr(key)))
+}
+
+fn shorten(text: &str) -> String {
+    if text.chars().count() <= NODE_TEXT_CHARS {
+        text.to_string()
+    } else {
+        let mut short: String = text.chars().take(NODE_TEXT_CHARS - 3).collect();
+        short.push_str("...");
+        short
+    }
+}
@@ -0,0 +1,165 @@
+use ggez::{
+    graphics::{self, Color, DrawParam, PxScale, Rect, TextFragment},
+    Context, GameResult,
+};
+
+use crate::ecs::assets::asset_manager::{AssetManager, ImageHandle};
+
+/// How a font is described in its json file, for example
+/// `{ "kind": "ttf", "path": "/fonts/pixel.ttf", "size": 16 }` or
+/// `{ "kind": "bitmap", "image": "/fonts/pixel.png", "glyph_width": 8, "glyph_height": 8,
+/// "characters": " !\"#...", "scale": 2 }`, where the image is a grid of glyphs in the same
+/// order as `characters`.
+#[derive(Deserialize, Debug)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum FontConfig {
+    Ttf {
+        path: String,
+        size: f32,
+    },
+    Bitmap {
+        image: String,
+        glyph_width: f32,
+        glyph_height: f32,
+        characters: String,
+        #[serde(default = "default_bitmap_scale")]
+        scale: f32,
+        #[serde(default)]
+        spacing: f32,
+    },
+}
+
+fn default_bitmap_scale() -> f32 {
+    1.0
+}
+
+#[derive(Clone, Debug)]
+pub struct BitmapFont {
+    pub image: ImageHandle,
+    pub glyph_width: f32,
+    pub glyph_height: f32,
+    pub characters: Vec<char>,
+    pub scale: f32,
+    pub spacing: f32,
+}
+
+#[derive(Clone, Debug)]
+pub enum UiFont {
+    Ttf { font: graphics::Font, size: f32 },
+    Bitmap(BitmapFont),
+}
+
+impl Default for UiFont {
+    fn default() -> Self {
+        UiFont::Ttf {
+            font: graphics::Font::default(),
+            size: graphics::Font::DEFAULT_FONT_SCALE,
+        }
+    }
+}
+
+impl UiFont {
+    pub fn text_width(&self, ctx: &Context, text: &str) -> f32 {
+        match self {
+            UiFont::Ttf { .. } => self.ttf_text(text, Color::WHITE).width(ctx),
+            UiFont::Bitmap(bitmap) => text.chars().count() as f32 * bitmap.advance(),
+        }
+    }
+
+    pub fn line_height(&self, ctx: &Context) -> f32 {
+        match self {
+            UiFont::Ttf { .. } => self.ttf_text("Ag", Color::WHITE).height(ctx),
+            UiFont::Bitmap(bitmap) => (bitmap.glyph_height + bitmap.spacing) * bitmap.scale,
+        }
+    }
+
+    /// X offset of every character of `text` from the start of the line.
+    pub fn glyph_offsets(&self, ctx: &Context, text: &str) -> Vec<f32> {
+        match self {
+            UiFont::Ttf { .. } => {
+                // Trailing spaces don't count towards a text's width, a sentinel keeps them
+                let sentinel = self.text_width(ctx, "|");
+                let mut prefix = String::new();
+                text.chars()
+                    .map(|ch| {
+                        let offset = self.text_width(ctx, &format!("{}|", prefix)) - sentinel;
+                        prefix.push(ch);
+                        offset
+                    })
+                    .collect()
+            }
+            UiFont::Bitmap(bitmap) => (0..text.chars().count())
+                .map(|index| index as f32 * bitmap.advance())
+                .collect(),
+        }
+    }
+
+    pub fn draw_text(
+        &self,
+        ctx: &mut Context,
+        asset_manager: &AssetManager,
+        text: &str,
+        dest: [f32; 2],
+        color: Color,
+    ) -> GameResult {
+        match self {
+            UiFont::Ttf { .. } => {
+                graphics::draw(ctx, &self.ttf_text(text, color), DrawParam::default().dest(dest))
+            }
+            UiFont::Bitmap(bitmap) => bitmap.draw_text(ctx, asset_manager, text, dest, color),
+        }
+    }
+
+    fn ttf_text(&self, text: &str, color: Color) -> graphics::Text {
+        let (font, size) = match self {
+            UiFont::Ttf { font, size } => (*font, *size),
+            UiFont::Bitmap(_) => (graphics::Font::default(), graphics::Font::DEFAULT_FONT_SCALE),
+        };
+        graphics::Text::new(TextFragment {
+            text: text.to_string(),
+            color: Some(color),
+            font: Some(font),
+            scale: Some(PxScale::from(size)),
+        })
+    }
+}
+
+impl BitmapFont {
+    fn advance(&self) -> f32 {
+        (self.glyph_width + self.spacing) * self.scale
+    }
+
+    fn draw_text(
+        &self,
+        ctx: &mut Context,
+        asset_manager: &AssetManager,
+        text: &str,
+        dest: [f32; 2],
+        color: Color,
+    ) -> GameResult {
+        let image = asset_manager.image(self.image);
+        let (width, height) = (image.width() as f32, image.height() as f32);
+        let columns = ((width / self.glyph_width) as usize).max(1);
+
+        for (index, ch) in text.chars().enumerate() {
+            let glyph = match self.characters.iter().position(|c| *c == ch) {
+                Some(glyph) => glyph,
+                None => continue,
+            };
+            let src = Rect::new(
+                (glyph % columns) as f32 * self.glyph_width / width,
+                (glyph / columns) as f32 * self.glyph_height / height,
+                self.glyph_width / width,
+                self.glyph_height / height,
+            );
+            let param = DrawParam::default()
+                .src(src)
+                .dest([dest[0] + index as f32 * self.advance(), dest[1]])
+                .scale([self.scale, self.scale])
+                .color(color);
+            graphics::draw(ctx, image, param)?;
+        }
+
+        Ok(())
+    }
+}
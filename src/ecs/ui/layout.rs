@@ -0,0 +1,53 @@
+use ggez::graphics::Rect;
+
+use crate::ecs::systems::physics_system::physics::{Position, Size};
+
+/// Point of the parent rect a node is attached to. The node's own matching point sits on
+/// it, so `BottomCenter` keeps a node horizontally centered and resting on the bottom edge.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Anchor {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    CenterLeft,
+    Center,
+    CenterRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+    /// Takes the whole parent rect, shrunk by the offset on every side.
+    Fill,
+}
+
+impl Anchor {
+    pub fn place(self, parent: Rect, size: Size, offset: Position) -> Rect {
+        if self == Anchor::Fill {
+            return Rect::new(
+                parent.x + offset.x,
+                parent.y + offset.y,
+                parent.w - offset.x * 2.0,
+                parent.h - offset.y * 2.0,
+            );
+        }
+
+        let (x_factor, y_factor) = match self {
+            Anchor::TopLeft => (0.0, 0.0),
+            Anchor::TopCenter => (0.5, 0.0),
+            Anchor::TopRight => (1.0, 0.0),
+            Anchor::CenterLeft => (0.0, 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::CenterRight => (1.0, 0.5),
+            Anchor::BottomLeft => (0.0, 1.0),
+            Anchor::BottomCenter => (0.5, 1.0),
+            Anchor::BottomRight => (1.0, 1.0),
+            Anchor::Fill => unreachable!(),
+        };
+
+        Rect::new(
+            parent.x + (parent.w - size.width) * x_factor + offset.x,
+            parent.y + (parent.h - size.height) * y_factor + offset.y,
+            size.width,
+            size.height,
+        )
+    }
+}
@@ -0,0 +1,156 @@
+use ggez::graphics::Color;
+
+pub const EMPHASIS_COLOR: Color = Color::new(1.0, 0.87, 0.35, 1.0);
+
+/// Per-glyph animation applied while drawing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TextEffect {
+    None,
+    /// Glyphs bob up and down one after the other.
+    Wave,
+    /// Glyphs jitter randomly.
+    Shake,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextStyle {
+    pub color: Option<Color>,
+    pub emphasis: bool,
+    pub bold: bool,
+    pub effect: TextEffect,
+}
+
+impl TextStyle {
+    /// Colour to draw with, given the label's own colour.
+    pub fn resolve_color(&self, base: Color) -> Color {
+        match (self.color, self.emphasis) {
+            (Some(color), _) => color,
+            (None, true) => EMPHASIS_COLOR,
+            (None, false) => base,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StyledChar {
+    pub ch: char,
+    pub style: TextStyle,
+    /// Seconds the typewriter waits before revealing this character.
+    pub pause: f32,
+}
+
+/// Parses dialogue markup into styled characters.
+///
+/// Supported tags: `[pause=0.5]`, `[color=red]...[/color]` (a name or `#rrggbb`),
+/// `[em]...[/em]`, `[b]...[/b]`, `[wave]...[/wave]` and `[shake]...[/shake]`. Anything else
+/// in brackets is kept as plain text.
+pub fn parse_markup(text: &str) -> Vec<StyledChar> {
+    let mut chars = Vec::new();
+    let mut colors: Vec<Color> = Vec::new();
+    let mut effects: Vec<TextEffect> = Vec::new();
+    let mut emphasis = 0;
+    let mut bold = 0;
+    let mut pending_pause = 0.0;
+    let mut rest = text;
+
+    while let Some(ch) = rest.chars().next() {
+        if ch == '[' {
+            if let Some(end) = rest.find(']') {
+                let tag = &rest[1..end];
+                let handled = match tag.split_once('=') {
+                    Some(("pause", seconds)) => match seconds.parse::<f32>() {
+                        Ok(seconds) => {
+                            pending_pause += seconds;
+                            true
+                        }
+                        Err(_) => false,
+                    },
+                    Some(("color", value)) => match parse_color(value) {
+                        Some(color) => {
+                            colors.push(color);
+                            true
+                        }
+                        None => false,
+                    },
+                    _ => match tag {
+                        "/color" => colors.pop().is_some(),
+                        "em" => {
+                            emphasis += 1;
+                            true
+                        }
+                        "/em" if emphasis > 0 => {
+                            emphasis -= 1;
+                            true
+                        }
+                        "b" => {
+                            bold += 1;
+                            true
+                        }
+                        "/b" if bold > 0 => {
+                            bold -= 1;
+                            true
+                        }
+                        "wave" => {
+                            effects.push(TextEffect::Wave);
+                            true
+                        }
+                        "shake" => {
+                            effects.push(TextEffect::Shake);
+                            true
+                        }
+                        "/wave" | "/shake" => effects.pop().is_some(),
+                        _ => false,
+                    },
+                };
+
+                if handled {
+                    rest = &rest[end + 1..];
+                    continue;
+                }
+            }
+        }
+
+        // Whitespace is dropped when wrapping, so pauses wait for the next visible character
+        let pause = if ch.is_whitespace() { 0.0 } else { pending_pause };
+        chars.push(StyledChar {
+            ch,
+            style: TextStyle {
+                color: colors.last().copied(),
+                emphasis: emphasis > 0,
+                bold: bold > 0,
+                effect: effects.last().copied().unwrap_or(TextEffect::None),
+            },
+            pause,
+        });
+        if !ch.is_whitespace() {
+            pending_pause = 0.0;
+        }
+        rest = &rest[ch.len_utf8()..];
+    }
+
+    chars
+}
+
+/// Plain text of a run of styled characters.
+pub fn plain_text(chars: &[StyledChar]) -> String {
+    chars.iter().map(|styled| styled.ch).collect()
+}
+
+fn parse_color(value: &str) -> Option<Color> {
+    match value {
+        "white" => Some(Color::WHITE),
+        "black" => Some(Color::BLACK),
+        "red" => Some(Color::from_rgb(235, 77, 75)),
+        "green" => Some(Color::from_rgb(106, 176, 76)),
+        "blue" => Some(Color::from_rgb(72, 126, 176)),
+        "yellow" => Some(Color::from_rgb(249, 202, 36)),
+        "orange" => Some(Color::from_rgb(240, 147, 43)),
+        "purple" => Some(Color::from_rgb(190, 46, 221)),
+        "gray" | "grey" => Some(Color::from_rgb(149, 165, 166)),
+        hex if hex.starts_with('#') && hex.len() == 7 => {
+            let value = u32::from_str_radix(&hex[1..], 16).ok()?;
+            Some(Color::from_rgb_u32(value))
+        }
+        _ => None,
+    }
+}
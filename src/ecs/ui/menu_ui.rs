@@ -0,0 +1,143 @@
+use ggez::{
+    event::KeyCode,
+    graphics::{Color, Rect},
+    Context, GameResult,
+};
+
+use super::{
+    layout::Anchor,
+    widget::{Label, List, NineSlice, Node, Widget},
+};
+use crate::ecs::{
+    assets::asset_manager::AssetManager,
+    scenes::shared_state::UiImages,
+    systems::{input_system::input_system::InteractionSound, physics_system::physics::Size},
+};
+
+const MENU_W: f32 = 420.0;
+const MENU_ITEM_H: f32 = 26.0;
+const MENU_PADDING: f32 = 24.0;
+const TITLE_H: f32 = 34.0;
+const NINE_SLICE_BORDER: f32 = 12.0;
+
+/// What a key did to a menu.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MenuInput {
+    None,
+    Moved,
+    Chosen(usize),
+    /// Left or right on an entry, -1 or 1.
+    Adjusted(usize, i32),
+    Back,
+}
+
+impl MenuInput {
+    pub fn sound(self) -> Option<InteractionSound> {
+        match self {
+            // Only some entries can be adjusted, their scene decides
+            MenuInput::None | MenuInput::Adjusted(..) => None,
+            MenuInput::Moved => Some(InteractionSound::Move),
+            MenuInput::Chosen(_) => Some(InteractionSound::Select),
+            MenuInput::Back => Some(InteractionSound::Back),
+        }
+    }
+}
+
+/// A centered box with a title and a list of entries, used by the title screen and the
+/// in-game menus.
+pub struct MenuUi {
+    root: Node,
+}
+
+impl MenuUi {
+    pub fn new(ui_images: &UiImages) -> Self {
+        let menu_box = Node::new(
+            "menu.box",
+            Widget::Panel(Some(NineSlice {
+                image: ui_images.dialog_box,
+                border: NINE_SLICE_BORDER,
+            })),
+        )
+        .anchor(Anchor::Center)
+        .size(MENU_W, 0.0)
+        .child(
+            Node::new("menu.title", Widget::Label(Label::new("", Color::WHITE).centered()))
+                .anchor(Anchor::TopCenter)
+                .offset(0.0, MENU_PADDING)
+                .size(MENU_W - 2.0 * MENU_PADDING, TITLE_H),
+        )
+        .child(
+            Node::new("menu.items", Widget::List(List::new(Vec::new())))
+                .anchor(Anchor::TopLeft)
+                .offset(MENU_PADDING, MENU_PADDING + TITLE_H)
+                .size(MENU_W - 2.0 * MENU_PADDING, 0.0),
+        );
+
+        MenuUi {
+            root: Node::new("menu", Widget::Panel(None)).child(menu_box),
+        }
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        if let Some(label) = self.root.label_mut("menu.title") {
+            label.set_text(title);
+        }
+    }
+
+    /// Replaces the entries, keeping the selection, and fits the box around them.
+    pub fn set_items(&mut self, items: Vec<String>) {
+        let items_h = items.len() as f32 * MENU_ITEM_H;
+        if let Some(list) = self.root.list_mut("menu.items") {
+            list.selected = list.selected.min(items.len().saturating_sub(1));
+            list.items = items;
+        }
+        if let Some(node) = self.root.find_mut("menu.items") {
+            node.size.height = items_h;
+        }
+        if let Some(node) = self.root.find_mut("menu.box") {
+            node.size.height = 2.0 * MENU_PADDING + TITLE_H + items_h;
+        }
+    }
+
+    pub fn selected(&mut self) -> usize {
+        self.root.list_mut("menu.items").map_or(0, |list| list.selected)
+    }
+
+    /// Moves the selection down, returning whether it moved.
+    pub fn select_next(&mut self) -> bool {
+        self.move_selection(List::select_next)
+    }
+
+    /// Moves the selection up, returning whether it moved.
+    pub fn select_previous(&mut self) -> bool {
+        self.move_selection(List::select_previous)
+    }
+
+    pub fn key_down(&mut self, key: KeyCode) -> MenuInput {
+        match key {
+            KeyCode::Up if self.select_previous() => MenuInput::Moved,
+            KeyCode::Down if self.select_next() => MenuInput::Moved,
+            KeyCode::Left => MenuInput::Adjusted(self.selected(), -1),
+            KeyCode::Right => MenuInput::Adjusted(self.selected(), 1),
+            KeyCode::Return => MenuInput::Chosen(self.selected()),
+            KeyCode::Escape => MenuInput::Back,
+            _ => MenuInput::None,
+        }
+    }
+
+    fn move_selection(&mut self, step: fn(&mut List)) -> bool {
+        match self.root.list_mut("menu.items") {
+            Some(list) => {
+                let before = list.selected;
+                step(list);
+                list.selected != before
+            }
+            None => false,
+        }
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context, asset_manager: &AssetManager, screen: &Size) -> GameResult {
+        let screen_rect = Rect::new(0.0, 0.0, screen.width, screen.height);
+        self.root.draw(ctx, asset_manager, screen_rect)
+    }
+}
@@ -0,0 +1,150 @@
+use ggez::{graphics::Color, Context, GameResult};
+use rand::Rng;
+
+use super::{
+    font::UiFont,
+    markup::{plain_text, StyledChar, TextEffect},
+};
+use crate::ecs::assets::asset_manager::AssetManager;
+
+const WAVE_AMPLITUDE: f32 = 2.0;
+const WAVE_SPEED: f32 = 8.0;
+const WAVE_GLYPH_PHASE: f32 = 0.6;
+const SHAKE_AMPLITUDE: f32 = 1.0;
+
+pub type StyledLine = Vec<StyledChar>;
+
+/// A wrapped line with the x offset of each of its characters, measured once.
+#[derive(Clone, Debug)]
+pub struct LaidOutLine {
+    pub chars: StyledLine,
+    pub offsets: Vec<f32>,
+}
+
+/// Breaks styled text into lines no wider than `max_width`, keeping explicit line breaks.
+/// A single word wider than `max_width` gets a line of its own.
+pub fn wrap_text(ctx: &Context, font: &UiFont, chars: &[StyledChar], max_width: f32) -> Vec<LaidOutLine> {
+    let mut lines = Vec::new();
+
+    for paragraph in chars.split(|styled| styled.ch == '\n') {
+        let mut line: StyledLine = Vec::new();
+        let words = paragraph
+            .split(|styled| styled.ch.is_whitespace())
+            .filter(|word| !word.is_empty());
+
+        for word in words {
+            if line.is_empty() {
+                line.extend_from_slice(word);
+                continue;
+            }
+
+            let candidate = format!("{} {}", plain_text(&line), plain_text(word));
+            if font.text_width(ctx, &candidate) > max_width {
+                lines.push(line);
+                line = word.to_vec();
+            } else {
+                line.push(StyledChar {
+                    ch: ' ',
+                    pause: 0.0,
+                    ..word[0]
+                });
+                line.extend_from_slice(word);
+            }
+        }
+        lines.push(line);
+    }
+
+    lines
+        .into_iter()
+        .map(|chars| LaidOutLine {
+            offsets: font.glyph_offsets(ctx, &plain_text(&chars)),
+            chars,
+        })
+        .collect()
+}
+
+/// Groups wrapped lines into pages of at most `lines_per_page` lines.
+pub fn paginate(lines: Vec<LaidOutLine>, lines_per_page: usize) -> Vec<Vec<LaidOutLine>> {
+    let lines_per_page = lines_per_page.max(1);
+    let mut pages: Vec<Vec<LaidOutLine>> = lines
+        .chunks(lines_per_page)
+        .map(|page| page.to_vec())
+        .collect();
+    if pages.is_empty() {
+        pages.push(Vec::new());
+    }
+    pages
+}
+
+/// Draws the first `shown` characters of a line, one run per style and one glyph at a time
+/// for animated runs. `time` drives the effects.
+pub fn draw_styled_line(
+    ctx: &mut Context,
+    asset_manager: &AssetManager,
+    line: &LaidOutLine,
+    shown: usize,
+    origin: [f32; 2],
+    base_color: Color,
+    time: f32,
+) -> GameResult {
+    let font = asset_manager.ui_font();
+    let chars = &line.chars[..shown.min(line.chars.len())];
+    let mut start = 0;
+
+    while start < chars.len() {
+        let style = chars[start].style;
+        let end = chars[start..]
+            .iter()
+            .position(|styled| styled.style != style)
+            .map_or(chars.len(), |offset| start + offset);
+        let color = style.resolve_color(base_color);
+
+        if style.effect == TextEffect::None {
+            let dest = [origin[0] + line.offsets[start], origin[1]];
+            draw_run(ctx, asset_manager, font, &plain_text(&chars[start..end]), dest, color, style.bold)?;
+        } else {
+            for (index, styled) in chars.iter().enumerate().take(end).skip(start) {
+                let (dx, dy) = effect_offset(style.effect, time, index);
+                let dest = [origin[0] + line.offsets[index] + dx, origin[1] + dy];
+                draw_run(ctx, asset_manager, font, &styled.ch.to_string(), dest, color, style.bold)?;
+            }
+        }
+
+        start = end;
+    }
+
+    Ok(())
+}
+
+fn draw_run(
+    ctx: &mut Context,
+    asset_manager: &AssetManager,
+    font: &UiFont,
+    text: &str,
+    dest: [f32; 2],
+    color: Color,
+    bold: bool,
+) -> GameResult {
+    font.draw_text(ctx, asset_manager, text, dest, color)?;
+    if bold {
+        font.draw_text(ctx, asset_manager, text, [dest[0] + 1.0, dest[1]], color)?;
+    }
+    Ok(())
+}
+
+fn effect_offset(effect: TextEffect, time: f32, index: usize) -> (f32, f32) {
+    match effect {
+        TextEffect::None => (0.0, 0.0),
+        TextEffect::Wave => (
+            0.0,
+            (time * WAVE_SPEED + index as f32 * WAVE_GLYPH_PHASE).sin() * WAVE_AMPLITUDE,
+        ),
+        TextEffect::Shake => {
+            let mut rng = rand::thread_rng();
+            (
+                rng.gen_range(-SHAKE_AMPLITUDE..=SHAKE_AMPLITUDE),
+                rng.gen_range(-SHAKE_AMPLITUDE..=SHAKE_AMPLITUDE),
+            )
+        }
+    }
+}
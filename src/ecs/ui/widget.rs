@@ -0,0 +1,422 @@
+use ggez::{
+    graphics::{self, Color, DrawParam, Rect},
+    mint, Context, GameResult,
+};
+
+use super::{
+    font::UiFont,
+    layout::Anchor,
+    markup::{parse_markup, StyledChar},
+    text::{draw_styled_line, paginate, wrap_text, LaidOutLine},
+};
+use crate::ecs::{
+    assets::asset_manager::{AssetManager, ImageHandle},
+    systems::physics_system::physics::{Position, Size},
+};
+
+/// An image stretched to any size while keeping its `border` pixels wide frame unscaled.
+#[derive(Copy, Clone, Debug)]
+pub struct NineSlice {
+    pub image: ImageHandle,
+    pub border: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Label {
+    pub text: String,
+    pub color: Color,
+    pub centered: bool,
+    pub page: usize,
+    /// Characters revealed per second, `None` shows each page at once.
+    pub reveal_speed: Option<f32>,
+    chars: Vec<StyledChar>,
+    pages: Vec<Vec<LaidOutLine>>,
+    layout_size: Option<(f32, f32)>,
+    revealed: usize,
+    reveal_timer: f32,
+}
+
+impl Label {
+    pub fn new(text: &str, color: Color) -> Self {
+        Label {
+            text: text.to_string(),
+            color,
+            centered: false,
+            page: 0,
+            reveal_speed: None,
+            chars: parse_markup(text),
+            pages: Vec::new(),
+            layout_size: None,
+            revealed: usize::MAX,
+            reveal_timer: 0.0,
+        }
+    }
+
+    pub fn centered(mut self) -> Self {
+        self.centered = true;
+        self
+    }
+
+    /// Reveals the text character by character, `speed` characters per second.
+    pub fn typewriter(mut self, speed: f32) -> Self {
+        self.reveal_speed = Some(speed);
+        self.revealed = 0;
+        self
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        if self.text != text {
+            self.text = text.to_string();
+            self.chars = parse_markup(text);
+            self.layout_size = None;
+            self.restart();
+        }
+    }
+
+    /// Goes back to the first page and starts revealing it again.
+    pub fn restart(&mut self) {
+        self.page = 0;
+        self.reset_reveal();
+    }
+
+    /// Number of pages the text overflowed into the last time it was laid out.
+    pub fn page_count(&self) -> usize {
+        self.pages.len().max(1)
+    }
+
+    pub fn has_next_page(&self) -> bool {
+        self.page + 1 < self.page_count()
+    }
+
+    pub fn next_page(&mut self) {
+        if self.has_next_page() {
+            self.page += 1;
+            self.reset_reveal();
+        }
+    }
+
+    pub fn is_page_revealed(&self) -> bool {
+        self.revealed >= self.page_len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.is_page_revealed() && !self.has_next_page()
+    }
+
+    pub fn reveal_page(&mut self) {
+        self.revealed = usize::MAX;
+    }
+
+    /// Completes the current page if it is still being revealed, otherwise turns to the
+    /// next one. Returns false when there was nothing left to show.
+    pub fn advance(&mut self) -> bool {
+        if !self.is_page_revealed() {
+            self.reveal_page();
+            true
+        } else if self.has_next_page() {
+            self.next_page();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Moves the typewriter forward, returning how many characters were revealed.
+    pub fn update(&mut self, dt: f32) -> usize {
+        let speed = match self.reveal_speed {
+            Some(speed) if speed > 0.0 => speed,
+            _ => return 0,
+        };
+        let page: Vec<StyledChar> = match self.pages.get(self.page) {
+            Some(lines) => lines.iter().flat_map(|line| line.chars.iter().copied()).collect(),
+            None => return 0,
+        };
+
+        let revealed_before = self.revealed;
+        self.reveal_timer += dt;
+        while self.revealed < page.len() {
+            let cost = page[self.revealed].pause + 1.0 / speed;
+            if self.reveal_timer < cost {
+                break;
+            }
+            self.reveal_timer -= cost;
+            self.revealed += 1;
+        }
+        if self.revealed >= page.len() {
+            self.reveal_timer = 0.0;
+        }
+
+        self.revealed.saturating_sub(revealed_before)
+    }
+
+    fn reset_reveal(&mut self) {
+        self.revealed = if self.reveal_speed.is_some() { 0 } else { usize::MAX };
+        self.reveal_timer = 0.0;
+    }
+
+    fn page_len(&self) -> usize {
+        self.pages
+            .get(self.page)
+            .map_or(0, |lines| lines.iter().map(|line| line.chars.len()).sum())
+    }
+
+    fn layout(&mut self, ctx: &Context, font: &UiFont, rect: Rect) {
+        if self.layout_size == Some((rect.w, rect.h)) {
+            return;
+        }
+        let lines_per_page = (rect.h / font.line_height(ctx)).floor() as usize;
+        self.pages = paginate(wrap_text(ctx, font, &self.chars, rect.w), lines_per_page);
+        self.page = self.page.min(self.pages.len() - 1);
+        self.layout_size = Some((rect.w, rect.h));
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct List {
+    pub items: Vec<String>,
+    pub selected: usize,
+    pub focused: bool,
+    pub color: Color,
+    pub focus_color: Color,
+}
+
+impl List {
+    pub fn new(items: Vec<String>) -> Self {
+        List {
+            items,
+            selected: 0,
+            focused: true,
+            color: Color::WHITE,
+            focus_color: Color::from_rgb(255, 221, 89),
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.items.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Widget {
+    /// Groups children, optionally over a nine-slice background.
+    Panel(Option<NineSlice>),
+    /// An image scaled to fit the node.
+    Image(Option<ImageHandle>),
+    /// Word-wrapped text that pages when it overflows the node.
+    Label(Label),
+    /// Vertical list of options with a selected entry.
+    List(List),
+    /// Horizontal line across the middle of the node.
+    Separator(Color),
+}
+
+/// A retained UI element, laid out relative to its parent by anchor, offset and size.
+#[derive(Clone, Debug)]
+pub struct Node {
+    pub id: &'static str,
+    pub anchor: Anchor,
+    pub offset: Position,
+    pub size: Size,
+    pub visible: bool,
+    pub widget: Widget,
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    pub fn new(id: &'static str, widget: Widget) -> Self {
+        Node {
+            id,
+            anchor: Anchor::Fill,
+            offset: Position { x: 0.0, y: 0.0 },
+            size: Size {
+                width: 0.0,
+                height: 0.0,
+            },
+            visible: true,
+            widget,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    pub fn offset(mut self, x: f32, y: f32) -> Self {
+        self.offset = Position { x, y };
+        self
+    }
+
+    pub fn size(mut self, width: f32, height: f32) -> Self {
+        self.size = Size { width, height };
+        self
+    }
+
+    pub fn child(mut self, child: Node) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn find_mut(&mut self, id: &str) -> Option<&mut Node> {
+        if self.id == id {
+            return Some(self);
+        }
+        self.children.iter_mut().find_map(|child| child.find_mut(id))
+    }
+
+    pub fn label_mut(&mut self, id: &str) -> Option<&mut Label> {
+        match self.find_mut(id).map(|node| &mut node.widget) {
+            Some(Widget::Label(label)) => Some(label),
+            _ => None,
+        }
+    }
+
+    pub fn list_mut(&mut self, id: &str) -> Option<&mut List> {
+        match self.find_mut(id).map(|node| &mut node.widget) {
+            Some(Widget::List(list)) => Some(list),
+            _ => None,
+        }
+    }
+
+    pub fn set_visible(&mut self, id: &str, visible: bool) {
+        if let Some(node) = self.find_mut(id) {
+            node.visible = visible;
+        }
+    }
+
+    pub fn set_image(&mut self, id: &str, image: Option<ImageHandle>) {
+        if let Some(node) = self.find_mut(id) {
+            node.widget = Widget::Image(image);
+        }
+    }
+
+    /// Lays the node out inside `parent` and draws it and its children.
+    pub fn draw(&mut self, ctx: &mut Context, asset_manager: &AssetManager, parent: Rect) -> GameResult {
+        if !self.visible {
+            return Ok(());
+        }
+
+        let rect = self.anchor.place(parent, self.size, self.offset);
+
+        match &mut self.widget {
+            Widget::Panel(Some(background)) => draw_nine_slice(ctx, asset_manager, background, rect)?,
+            Widget::Panel(None) => (),
+            Widget::Image(Some(image)) => {
+                let image = asset_manager.image(*image);
+                let scale = (rect.w / image.width() as f32).min(rect.h / image.height() as f32);
+                let param = DrawParam::default().dest([rect.x, rect.y]).scale([scale, scale]);
+                graphics::draw(ctx, image, param)?;
+            }
+            Widget::Image(None) => (),
+            Widget::Label(label) => draw_label(ctx, asset_manager, label, rect)?,
+            Widget::List(list) => draw_list(ctx, asset_manager, list, rect)?,
+            Widget::Separator(color) => {
+                let y = rect.y + rect.h / 2.0;
+                let points = [
+                    mint::Point2 { x: rect.x, y },
+                    mint::Point2 { x: rect.x + rect.w, y },
+                ];
+                let line = graphics::Mesh::new_line(ctx, &points, 1.0, *color)?;
+                graphics::draw(ctx, &line, DrawParam::default())?;
+            }
+        }
+
+        for child in self.children.iter_mut() {
+            child.draw(ctx, asset_manager, rect)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn draw_nine_slice(
+    ctx: &mut Context,
+    asset_manager: &AssetManager,
+    nine_slice: &NineSlice,
+    rect: Rect,
+) -> GameResult {
+    let image = asset_manager.image(nine_slice.image);
+    let (width, height) = (image.width() as f32, image.height() as f32);
+    let border = nine_slice.border.min(width / 2.0).min(height / 2.0);
+
+    let src_xs = [0.0, border, width - border, width];
+    let src_ys = [0.0, border, height - border, height];
+    let dest_xs = [rect.x, rect.x + border, rect.x + rect.w - border, rect.x + rect.w];
+    let dest_ys = [rect.y, rect.y + border, rect.y + rect.h - border, rect.y + rect.h];
+
+    for row in 0..3 {
+        for column in 0..3 {
+            let src_w = src_xs[column + 1] - src_xs[column];
+            let src_h = src_ys[row + 1] - src_ys[row];
+            if src_w <= 0.0 || src_h <= 0.0 {
+                continue;
+            }
+
+            let param = DrawParam::default()
+                .src(Rect::new(
+                    src_xs[column] / width,
+                    src_ys[row] / height,
+                    src_w / width,
+                    src_h / height,
+                ))
+                .dest([dest_xs[column], dest_ys[row]])
+                .scale([
+                    (dest_xs[column + 1] - dest_xs[column]) / src_w,
+                    (dest_ys[row + 1] - dest_ys[row]) / src_h,
+                ]);
+            graphics::draw(ctx, image, param)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn draw_label(ctx: &mut Context, asset_manager: &AssetManager, label: &mut Label, rect: Rect) -> GameResult {
+    let font = asset_manager.ui_font();
+    label.layout(ctx, font, rect);
+    let line_h = font.line_height(ctx);
+    let time = ggez::timer::time_since_start(ctx).as_secs_f32();
+    let mut remaining = label.revealed;
+
+    for (index, line) in label.pages[label.page].iter().enumerate() {
+        let shown = line.chars.len().min(remaining);
+        remaining -= shown;
+
+        let x = if label.centered {
+            let line_width = line.offsets.last().map_or(0.0, |last| {
+                let last_char = line.chars[line.chars.len() - 1].ch.to_string();
+                last + font.text_width(ctx, &last_char)
+            });
+            rect.x + (rect.w - line_width) / 2.0
+        } else {
+            rect.x
+        };
+        let origin = [x, rect.y + index as f32 * line_h];
+        draw_styled_line(ctx, asset_manager, line, shown, origin, label.color, time)?;
+    }
+
+    Ok(())
+}
+
+fn draw_list(ctx: &mut Context, asset_manager: &AssetManager, list: &List, rect: Rect) -> GameResult {
+    let font = asset_manager.ui_font();
+    let line_h = font.line_height(ctx) + 4.0;
+
+    for (index, item) in list.items.iter().enumerate() {
+        let (text, color) = if index == list.selected && list.focused {
+            (format!("> {}", item), list.focus_color)
+        } else {
+            (format!("  {}", item), list.color)
+        };
+        font.draw_text(ctx, asset_manager, &text, [rect.x, rect.y + index as f32 * line_h], color)?;
+    }
+
+    Ok(())
+}
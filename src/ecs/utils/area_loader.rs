@@ -0,0 +1,141 @@
+use ggez::{graphics::Color, GameError, GameResult};
+use std::collections::BTreeMap;
+
+use super::super::assets::asset_manager::{AssetManager, ConfigHandle};
+use super::super::systems::{
+    lighting_system::lighting_system::LightJson,
+    physics_system::physics::{Position, Size},
+};
+use super::constants::{AREAS_CONFIG, INITIAL_PLAYER_POS, INTIAL_WORLD_H, INTIAL_WORLD_W, SCENE_FILE};
+use super::npcs_json_loader::PointJson;
+
+/// The areas the world is made of, from `areas.json`.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct AreasConfig {
+    /// Area the game starts in.
+    pub start: String,
+    pub areas: BTreeMap<String, AreaJson>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct AreaJson {
+    pub width: f32,
+    pub height: f32,
+    /// Scene file with the area's entities, which the level editor saves to.
+    pub scene: String,
+    /// Without a scene file the NPCs and desks are placed at random, rather than leaving the
+    /// area empty.
+    #[serde(default)]
+    pub random_layout: bool,
+    /// Where the player starts when it's the first area, its center by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spawn: Option<PointJson>,
+    /// Color the floor tiles are tinted with.
+    #[serde(default = "default_floor")]
+    pub floor: [f32; 3],
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub doors: Vec<DoorJson>,
+    /// Lights fixed to the area, as in `lighting.json`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lights: Vec<LightJson>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct DoorJson {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// Area it leads to.
+    pub to: String,
+    /// Where the player appears in that area.
+    pub spawn: PointJson,
+}
+
+fn default_floor() -> [f32; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+impl AreaJson {
+    pub fn size(&self) -> Size {
+        Size {
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    pub fn spawn_position(&self) -> Position {
+        self.spawn.map_or(
+            Position {
+                x: self.width / 2.0,
+                y: self.height / 2.0,
+            },
+            PointJson::position,
+        )
+    }
+
+    pub fn floor_color(&self) -> Color {
+        let [r, g, b] = self.floor;
+        Color::new(r, g, b, 1.0)
+    }
+}
+
+/// A single office laid out from `SCENE_FILE`, or at random, for when there's no areas file.
+impl Default for AreasConfig {
+    fn default() -> Self {
+        let office = AreaJson {
+            width: INTIAL_WORLD_W,
+            height: INTIAL_WORLD_H,
+            scene: SCENE_FILE.to_string(),
+            random_layout: true,
+            spawn: Some(PointJson {
+                x: INITIAL_PLAYER_POS.x,
+                y: INITIAL_PLAYER_POS.y,
+            }),
+            floor: default_floor(),
+            doors: Vec::new(),
+            lights: Vec::new(),
+        };
+        AreasConfig {
+            start: "office".to_string(),
+            areas: BTreeMap::from([("office".to_string(), office)]),
+        }
+    }
+}
+
+/// Reads the areas and checks them with `validate_areas`, failing with every problem found.
+pub fn load_areas(asset_manager: &AssetManager, areas_config: ConfigHandle) -> GameResult<AreasConfig> {
+    let config: AreasConfig = asset_manager.config(areas_config)?;
+    let problems = validate_areas(&config);
+    if problems.is_empty() {
+        Ok(config)
+    } else {
+        Err(GameError::ResourceLoadError(format!(
+            "{} has {} problems:\n  {}",
+            asset_manager.source().display(AREAS_CONFIG),
+            problems.len(),
+            problems.join("\n  ")
+        )))
+    }
+}
+
+/// Missing areas and empty ones, each prefixed with its JSON path, e.g.
+/// `areas.kitchen.doors[0].to: no area named cellar`.
+pub fn validate_areas(config: &AreasConfig) -> Vec<String> {
+    let mut problems = Vec::new();
+    if !config.areas.contains_key(&config.start) {
+        problems.push(format!("start: no area named {}", config.start));
+    }
+    for (name, area) in config.areas.iter() {
+        let path = format!("areas.{}", name);
+        if area.width <= 0.0 || area.height <= 0.0 {
+            problems.push(format!("{}: {}x{} isn't a size", path, area.width, area.height));
+        }
+        for (index, door) in area.doors.iter().enumerate() {
+            if !config.areas.contains_key(&door.to) {
+                problems.push(format!("{}.doors[{}].to: no area named {}", path, index, door.to));
+            }
+        }
+    }
+    problems
+}
@@ -0,0 +1,191 @@
+use std::path::PathBuf;
+
+use super::settings::Settings;
+
+pub const USAGE: &str = "\
+Usage: wye_2D [OPTIONS]
+
+Options:
+  --resources <PATH>    Load the game's resources from a folder or zip archive
+  --size <WIDTHxHEIGHT> Window size, e.g. 1280x720
+  --fullscreen          Start in fullscreen
+  --windowed            Start in a window
+  --seed <NUMBER>       Seed spawn positions, desk types and other random choices
+  --scene <NAME>        Scene to start in: title (default), office or dialogues
+  --area <NAME>         Area of areas.json the office opens in, instead of its start
+  --debug               Show the debug overlay
+  --script <FILE>       Console commands to run when the office loads
+                        (default console.txt in the config directory)
+  -h, --help            Print this help";
+
+/// The scene the game opens with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StartScene {
+    Title,
+    Office,
+    /// The dialogue editor, see `DialogueEditorScene`.
+    Dialogues,
+}
+
+/// Options given on the command line. The window ones override the saved settings for
+/// this run only, unless the player saves them from the settings menu.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CliOptions {
+    /// Overrides the usual lookup, see `ResourceSource::locate`.
+    pub resources_dir: Option<PathBuf>,
+    pub window_size: Option<(f32, f32)>,
+    pub fullscreen: Option<bool>,
+    pub seed: Option<u64>,
+    pub start_scene: StartScene,
+    pub start_area: Option<String>,
+    pub debug_overlay: bool,
+    pub console_script: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Default for CliOptions {
+    fn default() -> Self {
+        CliOptions {
+            resources_dir: None,
+            window_size: None,
+            fullscreen: None,
+            seed: None,
+            start_scene: StartScene::Title,
+            start_area: None,
+            debug_overlay: false,
+            console_script: None,
+            help: false,
+        }
+    }
+}
+
+impl CliOptions {
+    /// Parses the arguments after the program name, e.g. `std::env::args().skip(1)`.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<CliOptions, String> {
+        let mut options = CliOptions::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            // Both `--seed 42` and `--seed=42` work
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => {
+                    (flag.to_string(), Some(value.to_string()))
+                }
+                _ => (arg.clone(), None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{} needs a value", flag))
+            };
+
+            match flag.as_str() {
+                "--resources" => options.resources_dir = Some(PathBuf::from(value()?)),
+                "--size" => options.window_size = Some(parse_size(&value()?)?),
+                "--fullscreen" => options.fullscreen = Some(true),
+                "--windowed" => options.fullscreen = Some(false),
+                "--seed" => {
+                    let seed = value()?;
+                    let seed = seed
+                        .parse()
+                        .map_err(|_| format!("--seed expects a whole number, got {}", seed))?;
+                    options.seed = Some(seed);
+                }
+                "--scene" => {
+                    options.start_scene = match value()?.as_str() {
+                        "title" => StartScene::Title,
+                        "office" => StartScene::Office,
+                        "dialogues" => StartScene::Dialogues,
+                        other => {
+                            return Err(format!("Unknown scene {}, expected title, office or dialogues", other))
+                        }
+                    }
+                }
+                "--area" => options.start_area = Some(value()?),
+                "--debug" => options.debug_overlay = true,
+                "--script" => options.console_script = Some(PathBuf::from(value()?)),
+                "-h" | "--help" => options.help = true,
+                _ => return Err(format!("Unknown option {}", arg)),
+            }
+        }
+        Ok(options)
+    }
+
+    pub fn apply_to(&self, settings: &mut Settings) {
+        if let Some((width, height)) = self.window_size {
+            settings.video.width = width;
+            settings.video.height = height;
+        }
+        if let Some(fullscreen) = self.fullscreen {
+            settings.video.fullscreen = fullscreen;
+        }
+    }
+}
+
+fn parse_size(size: &str) -> Result<(f32, f32), String> {
+    let invalid = || format!("--size expects WIDTHxHEIGHT, got {}", size);
+    let (width, height) = size.split_once('x').ok_or_else(invalid)?;
+    let width: u32 = width.parse().map_err(|_| invalid())?;
+    let height: u32 = height.parse().map_err(|_| invalid())?;
+    if width == 0 || height == 0 {
+        return Err(invalid());
+    }
+    Ok((width as f32, height as f32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliOptions, String> {
+        CliOptions::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn no_arguments_give_the_defaults() {
+        assert_eq!(parse(&[]), Ok(CliOptions::default()));
+    }
+
+    #[test]
+    fn values_can_follow_the_flag_or_an_equals_sign() {
+        assert_eq!(parse(&["--seed=42"]).unwrap().seed, Some(42));
+        assert_eq!(parse(&["--seed", "42"]).unwrap().seed, Some(42));
+        assert_eq!(parse(&["--area=kitchen"]).unwrap().start_area.as_deref(), Some("kitchen"));
+    }
+
+    #[test]
+    fn flags_combine() {
+        let options = parse(&["--scene", "office", "--size", "1600x900", "--debug", "--windowed"]).unwrap();
+        assert_eq!(options.start_scene, StartScene::Office);
+        assert_eq!(options.window_size, Some((1600.0, 900.0)));
+        assert!(options.debug_overlay);
+        assert_eq!(options.fullscreen, Some(false));
+    }
+
+    #[test]
+    fn bad_values_are_reported() {
+        assert_eq!(parse(&["--size", "0x10"]), Err("--size expects WIDTHxHEIGHT, got 0x10".to_string()));
+        assert_eq!(parse(&["--seed=abc"]), Err("--seed expects a whole number, got abc".to_string()));
+        assert_eq!(parse(&["--seed"]), Err("--seed needs a value".to_string()));
+        assert_eq!(
+            parse(&["--scene", "roof"]),
+            Err("Unknown scene roof, expected title, office or dialogues".to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_flags_are_reported() {
+        assert_eq!(parse(&["--fly"]), Err("Unknown option --fly".to_string()));
+        assert_eq!(parse(&["--fly=high"]), Err("Unknown option --fly=high".to_string()));
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size("1280x720"), Ok((1280.0, 720.0)));
+        assert!(parse_size("1280").is_err());
+        assert!(parse_size("1280x").is_err());
+        assert!(parse_size("-1x720").is_err());
+        assert!(parse_size("1280x0").is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use super::super::systems::physics_system::physics::Position;
+use super::super::systems::{physics_system::physics::Position, render_system::camera::CameraMode};
 
 pub const HUMANOID_W: f32 = 30.0;
 pub const HUMANOID_H: f32 = 60.0;
@@ -17,9 +17,96 @@ pub const INTIAL_WORLD_H: f32 = 1000.0;
 pub const DEFAULT_WINDOW_W: f32 = 1600.0;
 pub const DEFAULT_WINDOW_H: f32 = 800.0;
 
+pub const VIRTUAL_W: f32 = 1600.0;
+pub const VIRTUAL_H: f32 = 800.0;
+
 pub const DEFAULT_CAMERA_OFFSET: f32 = 0.0;
-pub const DEFAULT_CAMERA_SPEED: f32 = 125.0;
-pub const DEFAULT_CAMERA_W: f32 = DEFAULT_WINDOW_W;
-pub const DEFAULT_CAMERA_H: f32 = DEFAULT_WINDOW_H;
+pub const CAMERA_LERP_STIFFNESS: f32 = 6.0;
+pub const CAMERA_SPRING_STIFFNESS: f32 = 40.0;
+pub const CAMERA_SPRING_DAMPING: f32 = 12.0;
+pub const DEFAULT_CAMERA_MODE: CameraMode = CameraMode::Lerp { stiffness: CAMERA_LERP_STIFFNESS };
+pub const DEFAULT_CAMERA_LOOK_AHEAD: f32 = 60.0;
+pub const CAMERA_ZOOM_STEP: f32 = 0.25;
+pub const MIN_CAMERA_ZOOM: f32 = 0.5;
+pub const MAX_CAMERA_ZOOM: f32 = 3.0;
+pub const DEFAULT_CAMERA_W: f32 = VIRTUAL_W;
+pub const DEFAULT_CAMERA_H: f32 = VIRTUAL_H;
+
+
+pub const DEFAULT_TEXT_SPEED: f32 = 40.0;
+/// Optional font description in the resources folder, see `FontConfig`.
+pub const UI_FONT_CONFIG: &str = "/ui_font.json";
+
+pub const RESOURCES_DIR: &str = "./src/resources";
+/// The repository's resources, found by `cargo run` from any directory.
+pub const DEV_RESOURCES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/resources");
+pub const RESOURCES_ENV: &str = "WYE_RESOURCES";
+pub const RESOURCES_ARCHIVE: &str = "resources.zip";
+pub const ASSET_WATCH_INTERVAL: f32 = 1.0;
+
+pub const SCENE_FADE_DURATION: f32 = 0.35;
+
+pub const FRAME_GRAPH_SAMPLES: usize = 120;
+pub const FRAME_GRAPH_H: f32 = 60.0;
+pub const TARGET_FRAME_TIME: f32 = 1.0 / 60.0;
+/// How fast the debug overlay's system timings follow new measurements, from 0 to 1.
+pub const TIMING_SMOOTHING: f32 = 0.1;
+
+pub const CONSOLE_LOG_LINES: usize = 200;
+pub const CONSOLE_VISIBLE_LINES: usize = 12;
+pub const CONSOLE_PADDING: f32 = 8.0;
+/// Console commands run when the office loads, from the config directory.
+pub const CONSOLE_SCRIPT: &str = "console.txt";
+
+/// The office layout, saved by the level editor.
+pub const SCENE_FILE: &str = "/office_scene.json";
+/// Optional list of the areas the world is made of, see `AreasConfig`.
+pub const AREAS_CONFIG: &str = "/areas.json";
+/// Virtual pixels per second the editor's view scrolls with WASD.
+pub const EDITOR_PAN_SPEED: f32 = 600.0;
+pub const EDITOR_PANEL_W: f32 = 300.0;
+
+pub const AUDIO_CONFIG: &str = "/audio.json";
+pub const NPCS_CONFIG: &str = "/npcs_config.json";
+pub const NPCS_ATLAS: &str = "/npcs64.json";
+pub const OFFICE_ATLAS: &str = "/office.json";
+/// Schedule place that takes an NPC out of the office.
+pub const AWAY_PLACE: &str = "away";
+/// Place NPCs come in and leave through, if the config has one.
+pub const ENTRANCE_PLACE: &str = "entrance";
+pub const NPC_WALK_SPEED: f32 = 80.0;
+/// How far from the player's box an entity can be and still be interacted with.
+pub const INTERACTION_RANGE: f32 = 40.0;
+/// How close the player has to walk into a door to go through it.
+pub const DOOR_REACH: f32 = 4.0;
+
+pub const CLOCK_CONFIG: &str = "/clock.json";
+pub const CLOCK_START: &str = "08:45";
+/// In-game minutes per real second, a working day takes about nine minutes.
+pub const CLOCK_TIME_SCALE: f32 = 1.0;
+pub const MINUTES_PER_DAY: f32 = 24.0 * 60.0;
+pub const LIGHTING_CONFIG: &str = "/lighting.json";
+/// Side of the texture each light is drawn with.
+pub const LIGHT_GRADIENT_SIZE: u16 = 128;
+/// Pixels per cell of the light map when it's worked out on the CPU.
+pub const LIGHT_MAP_CPU_CELL: f32 = 8.0;
+pub const MUSIC_CROSSFADE: f32 = 1.5;
+pub const DIALOG_BLIP_INTERVAL: f32 = 0.07;
+/// Frames of the player's walking animation on which a foot touches the ground.
+pub const FOOTSTEP_FRAMES: &[usize] = &[1, 4];
+pub const DEFAULT_VOICE_PITCH: f32 = 1.0;
+
+pub const GAME_ID: &str = "wye_2D";
+pub const GAME_AUTHOR: &str = "rust_team";
+pub const SETTINGS_FILE: &str = "settings.json";
+/// Window sizes offered by the settings menu.
+pub const RESOLUTIONS: &[(f32, f32)] = &[
+    (1280.0, 720.0),
+    (1600.0, 800.0),
+    (1600.0, 900.0),
+    (1920.0, 1080.0),
+    (2560.0, 1440.0),
+];
 
-pub const NPC_COUNT: i32 = 4;
+pub const DEFAULT_LOCALE: &str = "en";
+pub const LOCALES: &[&str] = &["en", "es"];
@@ -0,0 +1,167 @@
+use ggez::{GameError, GameResult};
+use std::collections::HashMap;
+
+use super::super::assets::asset_manager::{AssetManager, ConfigHandle};
+use super::constants::DEFAULT_LOCALE;
+
+/// Per-locale string tables loaded from `/locales/<locale>.json`, flat objects mapping keys
+/// to text. Lookups fall back to `DEFAULT_LOCALE` and then to the key itself, so untranslated
+/// literal text still shows up.
+pub struct Localization {
+    locales: Vec<String>,
+    current: usize,
+    tables: HashMap<String, HashMap<String, String>>,
+    handles: Vec<(String, ConfigHandle)>,
+}
+
+impl Localization {
+    pub fn load(asset_manager: &mut AssetManager, locales: &[&str]) -> GameResult<Self> {
+        let mut localization = Localization {
+            locales: locales.iter().map(|locale| locale.to_string()).collect(),
+            current: 0,
+            tables: HashMap::new(),
+            handles: Vec::new(),
+        };
+
+        for locale in locales {
+            let handle = asset_manager.load_config(&locale_path(locale))?;
+            localization
+                .tables
+                .insert(locale.to_string(), asset_manager.config(handle)?);
+            localization.handles.push((locale.to_string(), handle));
+        }
+
+        localization.set_locale(DEFAULT_LOCALE);
+        Ok(localization)
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.locales[self.current]
+    }
+
+    pub fn locales(&self) -> &[String] {
+        &self.locales
+    }
+
+    /// Switches language, returns false for a locale that wasn't loaded.
+    pub fn set_locale(&mut self, locale: &str) -> bool {
+        match self.locales.iter().position(|loaded| loaded == locale) {
+            Some(index) => {
+                self.current = index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn previous_locale(&mut self) {
+        self.current = (self.current + self.locales.len() - 1) % self.locales.len();
+    }
+
+    pub fn next_locale(&mut self) {
+        self.current = (self.current + 1) % self.locales.len();
+    }
+
+    pub fn tr<'a>(&'a self, key: &'a str) -> &'a str {
+        [self.locale(), DEFAULT_LOCALE]
+            .iter()
+            .find_map(|locale| self.tables.get(*locale).and_then(|table| table.get(key)))
+            .map_or(key, |text| text.as_str())
+    }
+
+    /// Picks up an edited string table. Returns true if `handle` was one of them.
+    pub fn reload(&mut self, asset_manager: &AssetManager, handle: ConfigHandle) -> GameResult<bool> {
+        match self.handles.iter().find(|(_, loaded)| *loaded == handle) {
+            Some((locale, _)) => {
+                self.tables.insert(locale.clone(), asset_manager.config(handle)?);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Text of `key` in `locale` itself, without falling back.
+    pub fn text(&self, locale: &str, key: &str) -> Option<&str> {
+        self.tables.get(locale)?.get(key).map(String::as_str)
+    }
+
+    /// Whether any locale has `key`.
+    pub fn contains(&self, key: &str) -> bool {
+        self.tables.values().any(|table| table.contains_key(key))
+    }
+
+    /// Changes the text of `key` in `locale` until it's saved or reverted, e.g. from the
+    /// dialogue editor.
+    pub fn set_text(&mut self, locale: &str, key: &str, text: String) {
+        if let Some(table) = self.tables.get_mut(locale) {
+            table.insert(key.to_string(), text);
+        }
+    }
+
+    /// Writes `locale`'s table over its file. Keys keep their order in the file and new
+    /// ones go at the end.
+    pub fn save(&self, asset_manager: &AssetManager, locale: &str) -> GameResult {
+        let (table, handle) = match (self.tables.get(locale), self.handle(locale)) {
+            (Some(table), Some(handle)) => (table, handle),
+            _ => return Err(GameError::ResourceLoadError(format!("no locale {}", locale))),
+        };
+        let mut file: serde_json::Map<String, serde_json::Value> = asset_manager.config(handle)?;
+        let mut added: Vec<&String> = table.keys().filter(|key| !file.contains_key(*key)).collect();
+        added.sort();
+        for (key, value) in file.iter_mut() {
+            if let Some(text) = table.get(key) {
+                *value = serde_json::Value::String(text.clone());
+            }
+        }
+        for key in added {
+            file.insert(key.clone(), serde_json::Value::String(table[key].clone()));
+        }
+        asset_manager.save_config(&locale_path(locale), &file)
+    }
+
+    /// Drops the changes made with `set_text`, back to what the file says.
+    pub fn revert(&mut self, asset_manager: &AssetManager, locale: &str) -> GameResult {
+        match self.handle(locale) {
+            Some(handle) => self.reload(asset_manager, handle).map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    fn handle(&self, locale: &str) -> Option<ConfigHandle> {
+        self.handles
+            .iter()
+            .find(|(loaded, _)| loaded == locale)
+            .map(|(_, handle)| *handle)
+    }
+
+    /// For every locale, the keys it lacks among `used_keys` and the default locale's keys.
+    pub fn missing_keys(&self, used_keys: &[String]) -> Vec<(String, Vec<String>)> {
+        let mut expected: Vec<&String> = used_keys.iter().collect();
+        if let Some(default_table) = self.tables.get(DEFAULT_LOCALE) {
+            expected.extend(default_table.keys());
+        }
+        expected.sort();
+        expected.dedup();
+
+        self.locales
+            .iter()
+            .filter_map(|locale| {
+                let table = self.tables.get(locale)?;
+                let missing: Vec<String> = expected
+                    .iter()
+                    .filter(|key| !table.contains_key(key.as_str()))
+                    .map(|key| key.to_string())
+                    .collect();
+                if missing.is_empty() {
+                    None
+                } else {
+                    Some((locale.clone(), missing))
+                }
+            })
+            .collect()
+    }
+}
+
+fn locale_path(locale: &str) -> String {
+    format!("/locales/{}.json", locale)
+}
@@ -1,24 +1,320 @@
-use std::fs::read_to_string;
-use std::path::Path;
+use ggez::{GameError, GameResult};
+use std::collections::BTreeMap;
 
-use super::super::systems::input_system::interaction::Interaction;
+use super::super::assets::asset_manager::{AssetManager, ConfigHandle, ImageHandle};
+use super::super::components::{
+    npc::Npc,
+    schedule::{Schedule, ScheduleEntry, SchedulePlace},
+};
+use super::super::systems::{
+    input_system::interaction::Interaction,
+    physics_system::physics::Position,
+    schedule_system::clock::parse_clock_time,
+};
+use super::constants::{AWAY_PLACE, DEFAULT_VOICE_PITCH, NPCS_ATLAS, NPCS_CONFIG};
 
-#[derive(Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct NpcJson {
     pub id: u32,
     pub name: String,
+    /// Frame of the NPC atlas it's drawn with, `npc_<id>` by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sprite: Option<String>,
+    /// Image shown next to what it says, `/<name>.png` by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+    /// Where it stands in an office without a scene file, somewhere random by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spawn: Option<PointJson>,
     pub main_interaction: Option<Interaction>,
+    #[serde(default = "default_voice_pitch")]
+    pub voice_pitch: f32,
+    /// Where it goes and what it says through the day, by time.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedule: Vec<ScheduleJson>,
 }
-#[derive(Deserialize, Debug)]
-struct JsonObject {
-    npcs: Vec<NpcJson>,
+
+#[derive(Copy, Clone, Serialize, Deserialize, Debug)]
+pub struct PointJson {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl PointJson {
+    pub fn position(self) -> Position {
+        Position { x: self.x, y: self.y }
+    }
+}
+
+/// One part of an NPC's day, from `at` until the next entry's time.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ScheduleJson {
+    /// When it starts, `HH:MM`.
+    pub at: String,
+    /// What the NPC is up to, e.g. `coffee break`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub activity: String,
+    /// One of the config's places, `away` to leave the office, none to stay put.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub place: Option<String>,
+    /// What it says meanwhile, its `main_interaction` if none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interaction: Option<Interaction>,
+}
+
+impl NpcJson {
+    pub fn sprite_frame(&self) -> String {
+        self.sprite.clone().unwrap_or_else(|| format!("npc_{}", self.id))
+    }
+
+    pub fn avatar_path(&self) -> String {
+        self.avatar.clone().unwrap_or_else(|| format!("/{}.png", self.name))
+    }
+
+    pub fn spawn_position(&self) -> Option<Position> {
+        self.spawn.map(PointJson::position)
+    }
+
+    /// The NPC component, with the avatar loaded from `avatar_path`.
+    pub fn to_npc(&self, avatar: ImageHandle) -> Npc {
+        Npc {
+            id: self.id,
+            name: self.name.clone(),
+            sprite: self.sprite_frame(),
+            avatar,
+            voice_pitch: self.voice_pitch,
+        }
+    }
+
+    /// Its schedule with the places looked up, none if it has no schedule. Entries with a
+    /// bad time or place are skipped, `validate_npcs` reports them.
+    pub fn to_schedule(&self, places: &BTreeMap<String, PointJson>) -> Option<Schedule> {
+        if self.schedule.is_empty() {
+            return None;
+        }
+        let entries = self
+            .schedule
+            .iter()
+            .filter_map(|entry| {
+                let place = match entry.place.as_deref() {
+                    None => SchedulePlace::Stay,
+                    Some(AWAY_PLACE) => SchedulePlace::Away,
+                    Some(name) => SchedulePlace::At(places.get(name)?.position()),
+                };
+                Some(ScheduleEntry {
+                    minute: parse_clock_time(&entry.at)?,
+                    activity: entry.activity.clone(),
+                    place,
+                    interaction: entry.interaction.clone(),
+                })
+            })
+            .collect();
+        Some(Schedule::new(entries, self.main_interaction.clone()))
+    }
+}
+
+fn default_voice_pitch() -> f32 {
+    DEFAULT_VOICE_PITCH
+}
+
+/// The whole of `npcs_config.json`.
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct NpcsConfig {
+    pub npcs: Vec<NpcJson>,
+    /// Named spots of the office that schedules send NPCs to.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub places: BTreeMap<String, PointJson>,
+}
+
+impl NpcsConfig {
+    pub fn place(&self, name: &str) -> Option<Position> {
+        self.places.get(name).map(|point| point.position())
+    }
+}
+
+/// Reads the NPCs and checks them with `validate_npcs`, failing with every problem found.
+pub fn load_npcs(asset_manager: &AssetManager, npcs_config: ConfigHandle) -> GameResult<Vec<NpcJson>> {
+    Ok(load_npcs_config(asset_manager, npcs_config)?.npcs)
+}
+
+/// Like `load_npcs`, with the places.
+pub fn load_npcs_config(asset_manager: &AssetManager, npcs_config: ConfigHandle) -> GameResult<NpcsConfig> {
+    let config = read_npcs_config(asset_manager, npcs_config)?;
+    let problems = validate_npcs(asset_manager, &config);
+    if problems.is_empty() {
+        Ok(config)
+    } else {
+        Err(GameError::ResourceLoadError(format!(
+            "{} has {} problems:\n  {}",
+            asset_manager.source().display(NPCS_CONFIG),
+            problems.len(),
+            problems.join("\n  ")
+        )))
+    }
 }
 
-pub fn load_npcs() -> Vec<NpcJson> {
-    let json_file_path = Path::new("src/resources/npcs_config.json");
-    let json_file_str = read_to_string(json_file_path).expect("file not found");
+/// Reads the config as it is, for tools that show what's wrong with it.
+pub fn read_npcs_config(asset_manager: &AssetManager, npcs_config: ConfigHandle) -> GameResult<NpcsConfig> {
+    asset_manager.config(npcs_config)
+}
+
+/// Everything that would go wrong once the NPCs are in the game, each problem prefixed with
+/// its JSON path, e.g. `npcs[1].main_interaction.sub_interactions[0]: 2 options but 1
+/// sub_interactions`. Sprite frames are only checked once the NPC atlas is loaded.
+pub fn validate_npcs(asset_manager: &AssetManager, config: &NpcsConfig) -> Vec<String> {
+    let npcs = &config.npcs;
+    let mut problems = Vec::new();
+    let atlas = asset_manager.loaded_atlas(NPCS_ATLAS);
+
+    for (index, npc) in npcs.iter().enumerate() {
+        let path = format!("npcs[{}]", index);
+
+        if let Some(other) = npcs[..index].iter().position(|other| other.id == npc.id) {
+            problems.push(format!("{}.id: {} is already used by npcs[{}]", path, npc.id, other));
+        }
+        // Without a sprite of its own the frame comes from the id
+        let sprite_field = if npc.sprite.is_some() { "sprite" } else { "id" };
+        let frame = npc.sprite_frame();
+        if atlas.is_some_and(|atlas| !atlas.frame_names().any(|name| name == frame)) {
+            problems.push(format!("{}.{}: no frame {} in {}", path, sprite_field, frame, NPCS_ATLAS));
+        }
+
+        let avatar_field = if npc.avatar.is_some() { "avatar" } else { "name" };
+        let avatar = npc.avatar_path();
+        if npc.name.trim().is_empty() {
+            problems.push(format!("{}.name: empty", path));
+        }
+        if !asset_manager.exists(&avatar) {
+            problems.push(format!(
+                "{}.{}: no avatar image {}",
+                path,
+                avatar_field,
+                asset_manager.source().display(&avatar)
+            ));
+        }
+        if npc.voice_pitch <= 0.0 {
+            problems.push(format!("{}.voice_pitch: {} isn't above 0", path, npc.voice_pitch));
+        }
+
+        if let Some(interaction) = &npc.main_interaction {
+            problems.extend(interaction.problems(&format!("{}.main_interaction", path)));
+        }
+        problems.extend(validate_schedule(config, &npc.schedule, &format!("{}.schedule", path)));
+    }
+    problems
+}
+
+fn validate_schedule(config: &NpcsConfig, schedule: &[ScheduleJson], path: &str) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut previous: Option<f32> = None;
+
+    for (index, entry) in schedule.iter().enumerate() {
+        let path = format!("{}[{}]", path, index);
+        match parse_clock_time(&entry.at) {
+            Some(minute) if previous.is_some_and(|previous| minute <= previous) => {
+                problems.push(format!("{}.at: {} isn't after the entry before it", path, entry.at));
+            }
+            Some(minute) => previous = Some(minute),
+            None => problems.push(format!("{}.at: expected HH:MM, got {}", path, entry.at)),
+        }
+        if let Some(place) = &entry.place {
+            if place != AWAY_PLACE && !config.places.contains_key(place) {
+                problems.push(format!("{}.place: no place named {} in places", path, place));
+            }
+        }
+        if let Some(interaction) = &entry.interaction {
+            problems.extend(interaction.problems(&format!("{}.interaction", path)));
+        }
+    }
+    problems
+}
+
+/// Writes the config back, the game picks the changes up as it reloads it.
+pub fn save_npcs_config(asset_manager: &AssetManager, config: &NpcsConfig) -> GameResult {
+    asset_manager.save_config(NPCS_CONFIG, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::*;
+    use crate::ecs::assets::resources::ResourceSource;
+    use crate::ecs::utils::constants::DEV_RESOURCES_DIR;
+
+    fn asset_manager() -> AssetManager {
+        AssetManager::new(ResourceSource::Directory(PathBuf::from(DEV_RESOURCES_DIR)))
+    }
+
+    fn config(json: &str) -> NpcsConfig {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn missing_avatar(path: &str, field: &str, file: &str) -> String {
+        let file = Path::new(DEV_RESOURCES_DIR).join(file);
+        format!("{}.{}: no avatar image {}", path, field, file.display())
+    }
+
+    #[test]
+    fn a_valid_config_has_no_problems() {
+        let config = config(
+            r#"{ "npcs": [
+                { "id": 1, "name": "Julian", "main_interaction": { "dialog": "dialog.hi" } },
+                { "id": 2, "name": "Gio", "avatar": "/Gio.png" }
+            ] }"#,
+        );
+        assert!(validate_npcs(&asset_manager(), &config).is_empty());
+    }
+
+    #[test]
+    fn duplicate_ids() {
+        let config = config(
+            r#"{ "npcs": [
+                { "id": 1, "name": "Julian" },
+                { "id": 2, "name": "Gio" },
+                { "id": 1, "name": "Mauri" }
+            ] }"#,
+        );
+        assert_eq!(
+            validate_npcs(&asset_manager(), &config),
+            vec!["npcs[2].id: 1 is already used by npcs[0]"]
+        );
+    }
+
+    #[test]
+    fn missing_avatars() {
+        let config = config(
+            r#"{ "npcs": [
+                { "id": 1, "name": "Nobody" },
+                { "id": 2, "name": "Julian", "avatar": "/missing.png" }
+            ] }"#,
+        );
+        assert_eq!(
+            validate_npcs(&asset_manager(), &config),
+            vec![
+                missing_avatar("npcs[0]", "name", "Nobody.png"),
+                missing_avatar("npcs[1]", "avatar", "missing.png"),
+            ]
+        );
+    }
 
-    let deserialized_object: JsonObject =
-        serde_json::from_str::<JsonObject>(&json_file_str).expect("error while reading json");
-    deserialized_object.npcs
+    #[test]
+    fn interaction_problems_have_the_npc_path() {
+        let config = config(
+            r#"{ "npcs": [
+                { "id": 1, "name": "Julian", "main_interaction": {
+                    "dialog": "dialog.hi",
+                    "options": ["option.a", "option.b"],
+                    "sub_interactions": [{ "dialog": "dialog.a", "options": [] }]
+                } }
+            ] }"#,
+        );
+        assert_eq!(
+            validate_npcs(&asset_manager(), &config),
+            vec![
+                "npcs[0].main_interaction: 2 options but 1 sub_interactions",
+                "npcs[0].main_interaction.sub_interactions[0].options: empty, leave it out instead",
+            ]
+        );
+    }
 }
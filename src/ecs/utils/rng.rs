@@ -0,0 +1,18 @@
+use rand::{rngs::StdRng, SeedableRng};
+use std::cell::RefCell;
+
+thread_local! {
+    static GAME_RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+/// Restarts the game's random numbers from `seed`, so a run can be reproduced.
+pub fn seed(seed: u64) {
+    GAME_RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+/// Runs `f` with the game's random number generator. Anything that changes the world,
+/// like spawn positions or desk types, should draw from here instead of `thread_rng`;
+/// purely visual noise like camera shake doesn't need to.
+pub fn with_rng<T>(f: impl FnOnce(&mut StdRng) -> T) -> T {
+    GAME_RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
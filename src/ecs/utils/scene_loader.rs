@@ -0,0 +1,83 @@
+use ggez::{GameError, GameResult};
+
+use super::super::assets::asset_manager::{AssetManager, ConfigHandle};
+use super::super::components::interactable::Interactable;
+use super::constants::OFFICE_ATLAS;
+
+/// What an entity of the scene file is, with the fields of its component.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SceneEntityKind {
+    Desk { desk_type: i32, animation_id: i32 },
+    /// An NPC from the npcs config, by id.
+    Npc { id: u32 },
+    /// Decoration drawn from a frame of the office atlas.
+    Prop { frame: String },
+}
+
+/// An entity placed in the office, its position is the center of its collision box.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SceneEntity {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    #[serde(flatten)]
+    pub kind: SceneEntityKind,
+    /// What the player can do with it, a desk's monitor can be switched off by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interactable: Option<Interactable>,
+}
+
+/// The office layout, written by the level editor and loaded when the office starts.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct SceneFile {
+    pub entities: Vec<SceneEntity>,
+}
+
+/// Reads the scene file at `path`, every problem with it in the error, see `validate_scene`.
+pub fn load_scene(
+    asset_manager: &AssetManager,
+    scene_config: ConfigHandle,
+    path: &str,
+    desk_types: &[i32],
+) -> GameResult<SceneFile> {
+    let scene: SceneFile = asset_manager.config(scene_config)?;
+    let problems = validate_scene(asset_manager, &scene, desk_types);
+    if problems.is_empty() {
+        Ok(scene)
+    } else {
+        Err(GameError::ResourceLoadError(format!(
+            "{} has {} problems:\n  {}",
+            asset_manager.source().display(path),
+            problems.len(),
+            problems.join("\n  ")
+        )))
+    }
+}
+
+/// Desks without a sprite for their type and props without their frame, each prefixed with
+/// its JSON path, e.g. `entities[3].desk_type: no desk sprite for 7`. Frames are only checked
+/// once the office atlas is loaded.
+pub fn validate_scene(asset_manager: &AssetManager, scene: &SceneFile, desk_types: &[i32]) -> Vec<String> {
+    let atlas = asset_manager.loaded_atlas(OFFICE_ATLAS);
+    let missing_frame = |frame: &str| atlas.is_some_and(|atlas| !atlas.frame_names().any(|name| name == frame));
+    let mut problems = Vec::new();
+    for (index, entity) in scene.entities.iter().enumerate() {
+        let path = format!("entities[{}]", index);
+        match &entity.kind {
+            SceneEntityKind::Desk { desk_type, .. } if !desk_types.contains(desk_type) => {
+                problems.push(format!("{}.desk_type: no desk sprite for {}", path, desk_type));
+            }
+            SceneEntityKind::Prop { frame } if missing_frame(frame) => {
+                problems.push(format!("{}.frame: no frame {} in {}", path, frame, OFFICE_ATLAS));
+            }
+            _ => {}
+        }
+    }
+    problems
+}
+
+pub fn save_scene(asset_manager: &AssetManager, path: &str, scene: &SceneFile) -> GameResult {
+    asset_manager.save_config(path, scene)
+}
@@ -0,0 +1,306 @@
+use directories::ProjectDirs;
+use ggez::{
+    conf::{FullscreenType, WindowMode, WindowSetup},
+    event::KeyCode,
+    GameError, GameResult,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use strum_macros::EnumIter;
+
+use super::constants::{
+    DEFAULT_LOCALE, DEFAULT_WINDOW_H, DEFAULT_WINDOW_W, GAME_AUTHOR, GAME_ID, SETTINGS_FILE,
+};
+use crate::ecs::systems::audio_system::audio::Volumes;
+
+/// How the virtual resolution is scaled up to the window.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PixelScale {
+    /// Whole multiples only, with black bars around, so pixel art stays crisp.
+    Integer,
+    /// Fills as much of the window as possible.
+    Fractional,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct VideoSettings {
+    pub width: f32,
+    pub height: f32,
+    pub fullscreen: bool,
+    pub borderless: bool,
+    /// Only read when the window is created.
+    pub vsync: bool,
+    pub pixel_scale: PixelScale,
+}
+
+impl Default for VideoSettings {
+    fn default() -> Self {
+        VideoSettings {
+            width: DEFAULT_WINDOW_W,
+            height: DEFAULT_WINDOW_H,
+            fullscreen: false,
+            borderless: true,
+            vsync: true,
+            pixel_scale: PixelScale::Integer,
+        }
+    }
+}
+
+/// Something the player can bind keys to.
+#[derive(
+    Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Interact,
+    Back,
+    ZoomIn,
+    ZoomOut,
+}
+
+impl Action {
+    /// The key the game's code listens for, bound keys are translated into it.
+    pub fn canonical_key(self) -> KeyCode {
+        match self {
+            Action::MoveUp => KeyCode::Up,
+            Action::MoveDown => KeyCode::Down,
+            Action::MoveLeft => KeyCode::Left,
+            Action::MoveRight => KeyCode::Right,
+            Action::Interact => KeyCode::Return,
+            Action::Back => KeyCode::Escape,
+            Action::ZoomIn => KeyCode::Equals,
+            Action::ZoomOut => KeyCode::Minus,
+        }
+    }
+
+    /// Localization key of the action's name.
+    pub fn text_key(self) -> &'static str {
+        match self {
+            Action::MoveUp => "controls.move_up",
+            Action::MoveDown => "controls.move_down",
+            Action::MoveLeft => "controls.move_left",
+            Action::MoveRight => "controls.move_right",
+            Action::Interact => "controls.interact",
+            Action::Back => "controls.back",
+            Action::ZoomIn => "controls.zoom_in",
+            Action::ZoomOut => "controls.zoom_out",
+        }
+    }
+}
+
+/// Key bindings, stored by key name, e.g. `"move_up": ["Up", "W"]`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Controls {
+    pub bindings: BTreeMap<Action, Vec<String>>,
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        let bindings = [
+            (Action::MoveUp, vec![KeyCode::Up, KeyCode::W]),
+            (Action::MoveDown, vec![KeyCode::Down, KeyCode::S]),
+            (Action::MoveLeft, vec![KeyCode::Left, KeyCode::A]),
+            (Action::MoveRight, vec![KeyCode::Right, KeyCode::D]),
+            (Action::Interact, vec![KeyCode::Return, KeyCode::Space]),
+            (Action::Back, vec![KeyCode::Escape]),
+            (Action::ZoomIn, vec![KeyCode::Equals, KeyCode::NumpadAdd]),
+            (Action::ZoomOut, vec![KeyCode::Minus, KeyCode::NumpadSubtract]),
+        ];
+        Controls {
+            bindings: bindings
+                .into_iter()
+                .map(|(action, keys)| (action, keys.into_iter().map(key_name).collect()))
+                .collect(),
+        }
+    }
+}
+
+impl Controls {
+    pub fn action(&self, key: KeyCode) -> Option<Action> {
+        let name = key_name(key);
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.contains(&name))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn keys(&self, action: Action) -> Vec<KeyCode> {
+        self.bindings
+            .get(&action)
+            .map(|names| names.iter().filter_map(|name| key_from_name(name)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Bound keys become the key their action listens for, other keys stay as they are.
+    pub fn translate(&self, key: KeyCode) -> KeyCode {
+        self.action(key).map_or(key, Action::canonical_key)
+    }
+
+    /// Makes `key` the only key for `action`, taking it away from any other action. An
+    /// action left without keys gets `action`'s old ones, swapping the two. Returns false,
+    /// binding nothing, when there are none to give it.
+    pub fn bind(&mut self, action: Action, key: KeyCode) -> bool {
+        let name = key_name(key);
+        let previous: Vec<String> = self
+            .keys(action)
+            .into_iter()
+            .map(key_name)
+            .filter(|bound| *bound != name)
+            .collect();
+
+        let mut bindings = self.bindings.clone();
+        for (other, keys) in bindings.iter_mut().filter(|(other, _)| **other != action) {
+            if !keys.contains(&name) {
+                continue;
+            }
+            keys.retain(|bound| *bound != name);
+            if keys.is_empty() {
+                if previous.is_empty() {
+                    eprintln!("Not binding {} to {:?}, {:?} would have no keys left", name, action, other);
+                    return false;
+                }
+                *keys = previous.clone();
+            }
+        }
+        bindings.insert(action, vec![name]);
+        self.bindings = bindings;
+        true
+    }
+}
+
+/// The player's preferences, saved as json in the platform's config directory.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub video: VideoSettings,
+    pub audio: Volumes,
+    pub controls: Controls,
+    pub language: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            video: VideoSettings::default(),
+            audio: Volumes::default(),
+            controls: Controls::default(),
+            language: DEFAULT_LOCALE.to_string(),
+        }
+    }
+}
+
+impl Settings {
+    /// Where the settings live, the same folder ggez uses as its user config directory.
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(SETTINGS_FILE))
+    }
+
+    /// Reads the settings file. A missing file gives the defaults, a broken one is
+    /// reported and also gives the defaults.
+    pub fn load() -> Settings {
+        let path = match Settings::path() {
+            Some(path) if path.exists() => path,
+            _ => return Settings::default(),
+        };
+
+        let parsed = fs::read(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|bytes| serde_json::from_slice(&bytes).map_err(|error| error.to_string()));
+        match parsed {
+            Ok(settings) => settings,
+            Err(error) => {
+                eprintln!("Couldn't read {}, using the defaults: {}", path.display(), error);
+                Settings::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> GameResult {
+        let path = Settings::path().ok_or_else(|| {
+            GameError::FilesystemError("No config directory for the settings".to_string())
+        })?;
+        let to_error = |error: std::io::Error| {
+            GameError::FilesystemError(format!("{}: {}", path.display(), error))
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(to_error)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|error| GameError::FilesystemError(error.to_string()))?;
+        fs::write(&path, json).map_err(to_error)
+    }
+
+    pub fn window_mode(&self) -> WindowMode {
+        WindowMode {
+            width: self.video.width,
+            height: self.video.height,
+            maximized: false,
+            fullscreen_type: if self.video.fullscreen {
+                FullscreenType::Desktop
+            } else {
+                FullscreenType::Windowed
+            },
+            borderless: self.video.borderless,
+            min_width: 0.0,
+            min_height: 0.0,
+            max_width: 0.0,
+            max_height: 0.0,
+            resizable: true,
+            visible: true,
+            resize_on_scale_factor_change: true,
+        }
+    }
+
+    pub fn window_setup(&self) -> WindowSetup {
+        WindowSetup::default().vsync(self.video.vsync)
+    }
+}
+
+/// The platform's config directory for the game, e.g. `~/.config/wye_2D` on Linux.
+pub fn config_dir() -> Option<PathBuf> {
+    ProjectDirs::from("", GAME_AUTHOR, GAME_ID).map(|dirs| dirs.config_dir().to_path_buf())
+}
+
+/// Keys that can be bound, by the name used in the settings file.
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right,
+    KeyCode::Return, KeyCode::Escape, KeyCode::Space, KeyCode::Tab, KeyCode::Back,
+    KeyCode::LShift, KeyCode::RShift, KeyCode::LControl, KeyCode::RControl,
+    KeyCode::LAlt, KeyCode::RAlt,
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+    KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+    KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+    KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::Equals, KeyCode::Minus, KeyCode::Comma, KeyCode::Period, KeyCode::Slash,
+    KeyCode::Semicolon, KeyCode::Apostrophe, KeyCode::LBracket, KeyCode::RBracket,
+    KeyCode::NumpadAdd, KeyCode::NumpadSubtract, KeyCode::NumpadEnter,
+    KeyCode::Numpad0, KeyCode::Numpad1, KeyCode::Numpad2, KeyCode::Numpad3, KeyCode::Numpad4,
+    KeyCode::Numpad5, KeyCode::Numpad6, KeyCode::Numpad7, KeyCode::Numpad8, KeyCode::Numpad9,
+];
+
+pub fn key_name(key: KeyCode) -> String {
+    format!("{:?}", key)
+}
+
+/// How a key is called on screen, where it differs from its `key_name`.
+pub fn key_label(key: KeyCode) -> String {
+    match key {
+        KeyCode::Return => "Enter".to_string(),
+        _ => key_name(key),
+    }
+}
+
+pub fn key_from_name(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS.iter().copied().find(|key| key_name(*key) == name)
+}
@@ -4,16 +4,31 @@ extern crate serde;
 extern crate serde_json;
 
 use ecs::{
-    game_state::GameState,
-    utils::constants::{DEFAULT_WINDOW_H, DEFAULT_WINDOW_W},
+    assets::resources::ResourceSource,
+    scenes::scene_stack::SceneStack,
+    utils::{
+        cli::{CliOptions, USAGE},
+        constants::{GAME_AUTHOR, GAME_ID},
+        rng,
+        settings::Settings,
+    },
 };
-use ggez::conf::FullscreenType;
 use ggez::*;
 
 pub mod ecs {
+    pub mod assets {
+        pub mod asset_manager;
+        pub mod resources;
+    }
     pub mod components {
+        pub mod audio_emitter;
         pub mod desk;
+        pub mod door;
+        pub mod interactable;
+        pub mod light;
         pub mod npc;
+        pub mod prop;
+        pub mod schedule;
     }
     pub mod sprites {
         pub mod npc_sprite;
@@ -21,28 +36,94 @@ pub mod ecs {
         pub mod sprite;
         pub mod tile_sprite;
         pub mod office_sprite;
+        pub mod prop_sprite;
         pub mod draw;
     }
 
     pub mod systems {
+        pub mod area_system {
+            pub mod area;
+            pub mod area_system;
+        }
+
+        pub mod audio_system {
+            pub mod audio;
+            pub mod audio_system;
+        }
+
+        pub mod console_system {
+            pub mod console;
+            pub mod console_system;
+        }
+
+        pub mod debug_system {
+            pub mod debug_system;
+            pub mod frame_stats;
+        }
+
+        pub mod editor_system {
+            pub mod editor;
+            pub mod editor_system;
+        }
+
         pub mod input_system {
             pub mod input_system;
             pub mod interaction;
         }
 
+        pub mod lighting_system {
+            pub mod light_map;
+            pub mod lighting_system;
+        }
+
         pub mod physics_system {
             pub mod physics;
             pub mod physics_system;
         }
         pub mod render_system {
             pub mod camera;
+            pub mod render_queue;
             pub mod render_system;
+            pub mod viewport;
         }
+
+        pub mod schedule_system {
+            pub mod clock;
+            pub mod schedule_system;
+        }
+    }
+
+    pub mod scenes {
+        pub mod dialogue_editor_scene;
+        pub mod dialogue_scene;
+        pub mod pause_scene;
+        pub mod scene;
+        pub mod scene_stack;
+        pub mod settings_scene;
+        pub mod shared_state;
+        pub mod title_scene;
+    }
+
+    pub mod ui {
+        pub mod dialog_ui;
+        pub mod dialogue_graph;
+        pub mod font;
+        pub mod layout;
+        pub mod markup;
+        pub mod menu_ui;
+        pub mod text;
+        pub mod widget;
     }
 
     pub mod utils {
+        pub mod area_loader;
+        pub mod cli;
         pub mod constants;
+        pub mod localization;
         pub mod npcs_json_loader;
+        pub mod rng;
+        pub mod scene_loader;
+        pub mod settings;
     }
 
     pub mod atlas;
@@ -50,32 +131,50 @@ pub mod ecs {
 }
 
 fn main() -> GameResult {
+    let options = match CliOptions::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    if let Some(seed) = options.seed {
+        rng::seed(seed);
+    }
+
     let c = conf::Conf::new();
+    let mut settings = Settings::load();
+    options.apply_to(&mut settings);
 
-    let window_mode = ggez::conf::WindowMode {
-        width: DEFAULT_WINDOW_W,
-        height: DEFAULT_WINDOW_H,
-        maximized: false,
-        fullscreen_type: FullscreenType::Windowed,
-        borderless: true,
-        min_width: 0.0,
-        min_height: 0.0,
-        max_width: 0.0,
-        max_height: 0.0,
-        resizable: true,
-        visible: true,
-        resize_on_scale_factor_change: true,
+    let resources = ResourceSource::locate(options.resources_dir.clone())?;
+    let mut builder = ContextBuilder::new(GAME_ID, GAME_AUTHOR).default_conf(c);
+    if let Some(dir) = resources.directory() {
+        builder = builder.add_resource_path(dir);
+    }
+    let builder = builder
+        .window_setup(settings.window_setup())
+        .window_mode(settings.window_mode());
+
+    // ggez opens the audio device before the window, so without one we can still retry muted
+    let (mut ctx, event_loop, audio_enabled) = match builder.clone().build() {
+        Ok((ctx, event_loop)) => (ctx, event_loop, true),
+        Err(GameError::AudioError(error)) => {
+            eprintln!("No audio device, running muted: {}", error);
+            let (ctx, event_loop) = builder
+                .modules(conf::ModuleConf {
+                    audio: false,
+                    ..Default::default()
+                })
+                .build()?;
+            (ctx, event_loop, false)
+        }
+        Err(error) => return Err(error),
     };
 
-    let path = std::path::PathBuf::from("./src/resources");
-    let (mut ctx, event_loop) = ContextBuilder::new("wye_2D", "rust_team")
-        .default_conf(c)
-        .add_resource_path(path)
-        .window_mode(window_mode)
-        .build()
-        .unwrap();
-    graphics::set_window_title(&ctx, "Welcome to Wyeworks!");
-
-    let game_state = GameState::new(&mut ctx);
-    event::run(ctx, event_loop, game_state);
+    let scene_stack = SceneStack::new(&mut ctx, settings, resources, &options, audio_enabled)?;
+    event::run(ctx, event_loop, scene_stack);
 }